@@ -0,0 +1,56 @@
+use crate::models::difficulty::NumberRange;
+use crate::models::simulator::simulate;
+use crate::models::strategy::{Strategy, all_strategies, strategy_by_name};
+
+const USAGE: &str = "usage: guessing_game simulate [--min N] [--max N] [--games N] [--seed N] [--strategy bisection|random|golden|all]";
+
+/// コマンドライン引数を見て、窓を開かずに終わるなら終了コードを返す
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.first().map(String::as_str) {
+        Some("simulate") => Some(match simulate_command(&args[1..]) {
+            Ok(()) => 0,
+            Err(err) => {
+                eprintln!("{}\n{}", err, USAGE);
+                2
+            }
+        }),
+        Some("--help" | "-h") => {
+            println!("{}", USAGE);
+            Some(0)
+        }
+        _ => None,
+    }
+}
+
+fn simulate_command(args: &[String]) -> Result<(), String> {
+    let (mut min, mut max, mut games, mut seed) = (1i64, 100i64, 10_000usize, 0u64);
+    let mut strategy_name = "all".to_string();
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or_else(|| format!("{} の値がない", flag))?;
+        let invalid = |_| format!("{} の値 {} が不正", flag, value);
+        match flag.as_str() {
+            "--min" => min = value.parse().map_err(invalid)?,
+            "--max" => max = value.parse().map_err(invalid)?,
+            "--games" => games = value.parse().map_err(invalid)?,
+            "--seed" => seed = value.parse().map_err(invalid)?,
+            "--strategy" => strategy_name = value.clone(),
+            _ => return Err(format!("知らないオプション {}", flag)),
+        }
+    }
+
+    let range = NumberRange::new(min, max).ok_or("--min < --max にしてね")?;
+    let strategies: Vec<Box<dyn Strategy>> = if strategy_name == "all" {
+        all_strategies()
+    } else {
+        vec![strategy_by_name(&strategy_name).ok_or(format!("知らない作戦 {}", strategy_name))?]
+    };
+
+    for mut strategy in strategies {
+        // 作戦ごとに同じ答えの並びで比べる
+        let report = simulate(strategy.as_mut(), range, games, seed);
+        println!("{}", report);
+    }
+    Ok(())
+}
@@ -1,5 +1,22 @@
+pub mod attempts;
+pub mod bulls_cows;
+pub mod coach;
+pub mod daily;
+pub mod feedback;
 pub mod guess;
-pub mod input;
+pub mod history;
+pub mod hot_seat;
+pub mod keypad;
 pub mod message;
+pub mod mode_picker;
+pub mod number_line;
+pub mod race;
+pub mod range;
 pub mod reload;
+pub mod reverse;
+pub mod setup;
+pub mod slider;
+pub mod speed_run;
+pub mod stats;
+pub mod timer;
 pub mod title;
@@ -0,0 +1,25 @@
+use crate::models::game_state::{GameState, GameStatus};
+use crate::views::game::GameView;
+use gpui::Entity;
+use gpui::{div, prelude::*, rgb};
+
+pub fn attempts_element(
+    state_ticket: Entity<GameState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let state = state_ticket.read(cx);
+    let label = match state.max_attempts() {
+        Some(max) => format!("試行 {} / {}", state.attempts, max),
+        None => format!("試行 {}", state.attempts),
+    };
+    // 嘘をつかれるときは、まだつかれうる嘘の回数も出す
+    let lies = (state.max_lies() > 0 && state.status == GameStatus::Playing)
+        .then(|| format!("嘘はあと最大 {} 回", state.lies_left()));
+    div()
+        .flex()
+        .flex_row()
+        .gap_4()
+        .text_color(rgb(0xaaaaaa))
+        .child(label)
+        .children(lies)
+}
@@ -0,0 +1,226 @@
+use crate::models::bulls_cows_state::BullsCowsState;
+use crate::models::game_state::GameStatus;
+use crate::models::setup_state::SetupState;
+use crate::views::game::GameView;
+use gpui::{Entity, MouseButton};
+use gpui::{div, prelude::*, px, rgb};
+
+/// 桁数、重複、回数制限の設定と開始ボタン
+pub fn bulls_cows_setup_element(
+    setup_ticket: Entity<SetupState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    let settings = setup_ticket.read(cx).bulls_cows;
+    let attempts_label = match settings.max_attempts {
+        Some(max) => format!("回数制限: {}回", max),
+        None => "回数制限: なし".to_string(),
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .child(chip(
+                    format!("{}桁", settings.length),
+                    setup_ticket.clone(),
+                    |setup, cx| setup.cycle_code_length(cx),
+                ))
+                .child(chip(
+                    if settings.allow_repeats {
+                        "重複: あり".to_string()
+                    } else {
+                        "重複: なし".to_string()
+                    },
+                    setup_ticket.clone(),
+                    |setup, cx| setup.toggle_code_repeats(cx),
+                ))
+                .child(chip(attempts_label, setup_ticket.clone(), |setup, cx| {
+                    setup.cycle_code_attempts(cx)
+                })),
+        )
+        .child(
+            div()
+                .w(px(240.0))
+                .flex()
+                .justify_center()
+                .bg(rgb(0xf2f0ee))
+                .rounded_md()
+                .px_2()
+                .text_color(rgb(0x5a3e44))
+                .child("Start")
+                .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                    view_ticket.update(app_cx, |view, view_cx| {
+                        view.start_bulls_cows(view_cx);
+                    })
+                }),
+        )
+}
+
+/// 入力欄と、予想の履歴をピンで表した表
+pub fn bulls_cows_element(
+    bulls_cows_ticket: Entity<BullsCowsState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    let state = bulls_cows_ticket.read(cx);
+    let settings = state.settings;
+    let attempts = match settings.max_attempts {
+        Some(max) => format!("試行 {} / {}", state.history.len(), max),
+        None => format!("試行 {}", state.history.len()),
+    };
+    let message_color = match state.status {
+        GameStatus::Playing => rgb(0xffffff),
+        GameStatus::Won => rgb(0x7ed957),
+        GameStatus::Lost => rgb(0xff6b6b),
+    };
+    let input: Vec<char> = state.current_input.chars().collect();
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .child(
+            div()
+                .text_color(rgb(0xaaaaaa))
+                .child(format!(
+                    "Bulls & Cows : {}桁 {} {}",
+                    settings.length,
+                    if settings.allow_repeats {
+                        "重複あり"
+                    } else {
+                        "重複なし"
+                    },
+                    attempts
+                ))
+                .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                    view_ticket.update(app_cx, |view, view_cx| {
+                        view.open_setup(view_cx);
+                    })
+                }),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().flex().flex_row().gap_1().children(
+                    (0..settings.length).map(|i| digit_cell(input.get(i).copied(), true)),
+                ))
+                .child(button("GUESS!", bulls_cows_ticket.clone(), |state, cx| {
+                    state.sub_guess(cx)
+                }))
+                .child(button("Reload", bulls_cows_ticket.clone(), |state, cx| {
+                    state.reload(cx)
+                })),
+        )
+        .child(div().text_color(message_color).child(state.message.clone()))
+        .child(
+            div()
+                .id("code-history")
+                .flex()
+                .flex_col()
+                .gap_1()
+                .max_h(px(220.0))
+                .overflow_y_scroll()
+                .children(state.history.iter().enumerate().rev().map(|(i, record)| {
+                    div()
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap_2()
+                        .child(
+                            div()
+                                .w(px(24.0))
+                                .text_color(rgb(0xaaaaaa))
+                                .child(format!("{}.", i + 1)),
+                        )
+                        .child(
+                            div().flex().flex_row().gap_1().children(
+                                record
+                                    .guess
+                                    .iter()
+                                    .map(|d| digit_cell(char::from_digit(*d as u32, 10), false)),
+                            ),
+                        )
+                        .child(pegs(record.bulls, record.cows, settings.length))
+                })),
+        )
+}
+
+fn digit_cell(digit: Option<char>, editing: bool) -> impl IntoElement {
+    div()
+        .size(px(28.0))
+        .flex()
+        .items_center()
+        .justify_center()
+        .bg(rgb(0x222222))
+        .border_1()
+        .border_color(if editing {
+            rgb(0x007aff)
+        } else {
+            rgb(0x444444)
+        })
+        .text_color(rgb(0xffffff))
+        .child(digit.map(String::from).unwrap_or_default())
+}
+
+/// bulls は赤、cows は白、残りは暗いピン
+fn pegs(bulls: usize, cows: usize, length: usize) -> impl IntoElement {
+    div()
+        .flex()
+        .flex_row()
+        .gap_1()
+        .children((0..length).map(|i| {
+            let color = if i < bulls {
+                rgb(0xff4d4d)
+            } else if i < bulls + cows {
+                rgb(0xf2f0ee)
+            } else {
+                rgb(0x3a3a3a)
+            };
+            div().size(px(12.0)).rounded_full().bg(color)
+        }))
+}
+
+fn chip(
+    label: String,
+    setup_ticket: Entity<SetupState>,
+    on_click: fn(&mut SetupState, &mut Context<SetupState>),
+) -> impl IntoElement {
+    div()
+        .bg(rgb(0xf2f0ee))
+        .rounded_md()
+        .px_2()
+        .text_color(rgb(0x5a3e44))
+        .child(label)
+        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+            setup_ticket.update(app_cx, on_click)
+        })
+}
+
+fn button(
+    label: &'static str,
+    bulls_cows_ticket: Entity<BullsCowsState>,
+    on_click: fn(&mut BullsCowsState, &mut Context<BullsCowsState>),
+) -> impl IntoElement {
+    div()
+        .flex()
+        .items_center()
+        .bg(rgb(0xf2f0ee))
+        .rounded_md()
+        .px_2()
+        .text_color(rgb(0x5a3e44))
+        .child(label)
+        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+            bulls_cows_ticket.update(app_cx, on_click)
+        })
+}
@@ -0,0 +1,86 @@
+use crate::models::coach::{CoachReport, Rating};
+use crate::models::game_state::{GameState, GuessOutcome};
+use crate::models::number_format::group;
+use crate::views::game::GameView;
+use gpui::{Entity, MouseButton};
+use gpui::{div, prelude::*, rgb};
+
+/// 決着がついたゲームを1手ずつ振り返る
+pub fn coach_element(
+    state_ticket: Entity<GameState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let report = CoachReport::analyze(state_ticket.read(cx));
+    let view_ticket = cx.entity().clone();
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .text_color(rgb(0xffffff))
+        .child(format!(
+            "合計 {} 回 / 理論上の最短 {} 回 (必要な情報量 {:.1} ビット)  効率 {:.0}%",
+            report.total,
+            report.minimum,
+            report.bits_needed,
+            report.efficiency() * 100.0
+        ))
+        .child(
+            div()
+                .id("coach")
+                .flex()
+                .flex_col()
+                .max_h_64()
+                .overflow_y_scroll()
+                .text_sm()
+                .child(coach_row(
+                    ["#", "予想", "残り範囲", "二分探索", "情報量", "評価"].map(String::from),
+                    rgb(0xaaaaaa),
+                ))
+                .children(report.guesses.iter().enumerate().map(|(i, guess)| {
+                    let mark = match guess.outcome {
+                        GuessOutcome::Correct => "◎",
+                        GuessOutcome::TooHigh => "↓",
+                        GuessOutcome::TooLow => "↑",
+                    };
+                    let color = match guess.rating {
+                        Rating::Best => rgb(0x7ed957),
+                        Rating::Good => rgb(0x54a0ff),
+                        Rating::Fair => rgb(0xffc048),
+                        Rating::Wasted => rgb(0xff6b6b),
+                    };
+                    coach_row(
+                        [
+                            format!("{}.", i + 1),
+                            format!("{} {}", group(guess.value), mark),
+                            guess.possible.to_string(),
+                            group(guess.optimal),
+                            format!("{:.2} bit", guess.bits),
+                            format!("{} {:.0}%", guess.rating.label(), guess.efficiency * 100.0),
+                        ],
+                        color,
+                    )
+                })),
+        )
+        .child(
+            div()
+                .bg(rgb(0xf2f0ee))
+                .rounded_md()
+                .px_2()
+                .text_color(rgb(0x5a3e44))
+                .child("戻る")
+                .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                    view_ticket.update(app_cx, |view, view_cx| view.close_coach(view_cx))
+                }),
+        )
+}
+
+fn coach_row(cells: [String; 6], color: gpui::Rgba) -> impl IntoElement {
+    div()
+        .flex()
+        .flex_row()
+        .gap_2()
+        .text_color(color)
+        .children(cells.into_iter().map(|cell| div().w_24().child(cell)))
+}
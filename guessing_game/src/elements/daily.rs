@@ -0,0 +1,52 @@
+use crate::models::daily::DailyState;
+use crate::views::game::GameView;
+use gpui::{ClipboardItem, Entity, MouseButton};
+use gpui::{div, prelude::*, rgb};
+
+/// デイリーの日付と連続記録。終わったら共有用の結果を出す
+pub fn daily_element(
+    daily_ticket: Entity<DailyState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let daily = daily_ticket.read(cx);
+    let (current, best) = daily.streaks();
+    let share_text = daily
+        .today_record()
+        .filter(|record| record.finished)
+        .map(|record| record.share_text.clone());
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_1()
+        .text_color(rgb(0xaaaaaa))
+        .child(format!(
+            "Daily {}  連続 {}日 / 最高 {}日",
+            daily.today, current, best
+        ))
+        .when_some(share_text, |this, share_text| {
+            this.child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap_2()
+                    .child(div().text_color(rgb(0xffffff)).child(share_text.clone()))
+                    .child(
+                        div()
+                            .bg(rgb(0xf2f0ee))
+                            .rounded_md()
+                            .px_2()
+                            .text_color(rgb(0x5a3e44))
+                            .child("コピー")
+                            .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                                app_cx.write_to_clipboard(ClipboardItem::new_string(
+                                    share_text.clone(),
+                                ));
+                            }),
+                    ),
+            )
+        })
+        .child(daily.message.clone())
+}
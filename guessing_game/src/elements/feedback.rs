@@ -0,0 +1,155 @@
+use crate::models::effects_state::{EffectsState, Feedback};
+use crate::views::game::GameView;
+use gpui::{
+    Animation, AnimationExt, AnyElement, Bounds, Entity, MouseButton, canvas, fill, point, px, size,
+};
+use gpui::{div, prelude::*, rgb};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::PI;
+use std::time::Duration;
+
+/// 受け付けなかった予想のときに入力欄を左右に揺らす
+pub fn shake_element(
+    input: AnyElement,
+    effects_ticket: Entity<EffectsState>,
+    cx: &mut Context<GameView>,
+) -> AnyElement {
+    let effects = effects_ticket.read(cx);
+    let wrapper = div().relative().child(input);
+    if effects.reduced_motion || effects.feedback != Some(Feedback::Invalid) {
+        return wrapper.into_any_element();
+    }
+    wrapper
+        .with_animation(
+            ("shake", effects.seq),
+            Animation::new(Duration::from_millis(400)),
+            |this, delta| {
+                // 揺れ幅をだんだん小さくする
+                let offset = (delta * PI * 6.0).sin() * 8.0 * (1.0 - delta);
+                this.left(px(offset))
+            },
+        )
+        .into_any_element()
+}
+
+/// もっと大きい・小さいを矢印で示す。大きいなら下から、小さいなら上からすべり込む
+pub fn arrow_element(
+    effects_ticket: Entity<EffectsState>,
+    cx: &mut Context<GameView>,
+) -> AnyElement {
+    let effects = effects_ticket.read(cx);
+    let (arrow, color, from) = match effects.feedback {
+        Some(Feedback::TooLow) => ("▲", rgb(0x54a0ff), 16.0),
+        Some(Feedback::TooHigh) => ("▼", rgb(0xff9f43), -16.0),
+        _ => ("", rgb(0xffffff), 0.0),
+    };
+    let arrow = div().relative().w(px(24.0)).text_color(color).child(arrow);
+    if effects.reduced_motion || from == 0.0 {
+        return arrow.into_any_element();
+    }
+    arrow
+        .with_animation(
+            ("arrow", effects.seq),
+            Animation::new(Duration::from_millis(300)).with_easing(gpui::ease_out_quint()),
+            move |this, delta| this.top(px(from * (1.0 - delta))).opacity(delta),
+        )
+        .into_any_element()
+}
+
+/// 正解したときに画面いっぱいに紙吹雪を散らす
+pub fn burst_element(
+    effects_ticket: Entity<EffectsState>,
+    cx: &mut Context<GameView>,
+) -> AnyElement {
+    let effects = effects_ticket.read(cx);
+    let overlay = div().absolute().top_0().left_0().size_full();
+    if effects.reduced_motion || effects.feedback != Some(Feedback::Correct) {
+        return overlay.into_any_element();
+    }
+    let seq = effects.seq;
+    overlay
+        .with_animation(
+            ("burst", seq),
+            Animation::new(Duration::from_millis(1200)),
+            move |this, delta| {
+                this.child(
+                    canvas(
+                        |_, _, _| {},
+                        move |bounds, _, window, _app| {
+                            let colors = [0x7ed957, 0xffc048, 0x54a0ff, 0xff6b6b, 0xf2f0ee];
+                            let origin = point(
+                                bounds.origin.x + bounds.size.width / 2.,
+                                bounds.origin.y + bounds.size.height * 0.4,
+                            );
+                            // 同じ演出のあいだは同じ粒の動きになるように seq から作る
+                            let mut rng = StdRng::seed_from_u64(seq as u64);
+                            for i in 0..60 {
+                                let angle = rng.random_range(0.0..2.0 * PI);
+                                let speed = rng.random_range(150.0..380.0);
+                                let t = delta;
+                                // 打ち上げてから重力で落ちる
+                                let x = angle.cos() * speed * t;
+                                let y = angle.sin() * speed * t + 420.0 * t * t;
+                                let mut color = rgb(colors[i % colors.len()]);
+                                color.a = 1.0 - delta;
+                                let particle = Bounds::new(
+                                    point(origin.x + px(x), origin.y + px(y)),
+                                    size(px(5.), px(5.)),
+                                );
+                                window.paint_quad(fill(particle, color).corner_radii(px(1.)));
+                            }
+                        },
+                    )
+                    .size_full(),
+                )
+            },
+        )
+        .into_any_element()
+}
+
+/// 効果音と動きの設定
+pub fn effects_settings_element(
+    effects_ticket: Entity<EffectsState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let effects = effects_ticket.read(cx);
+    div()
+        .flex()
+        .flex_row()
+        .gap_2()
+        .child(toggle_button(
+            if effects.muted {
+                "音: なし"
+            } else {
+                "音: あり"
+            },
+            effects_ticket.clone(),
+            EffectsState::toggle_muted,
+        ))
+        .child(toggle_button(
+            if effects.reduced_motion {
+                "動き: 減らす"
+            } else {
+                "動き: 通常"
+            },
+            effects_ticket.clone(),
+            EffectsState::toggle_reduced_motion,
+        ))
+}
+
+fn toggle_button(
+    label: &'static str,
+    effects_ticket: Entity<EffectsState>,
+    on_click: fn(&mut EffectsState, &mut Context<EffectsState>),
+) -> impl IntoElement {
+    div()
+        .bg(rgb(0xf2f0ee))
+        .rounded_md()
+        .px_2()
+        .text_color(rgb(0x5a3e44))
+        .child(label)
+        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+            effects_ticket.update(app_cx, on_click)
+        })
+}
@@ -1,14 +1,26 @@
 use crate::models::game_state::GameState;
-use gpui::{Entity, MouseButton};
+use gpui::{Entity, FocusHandle, MouseButton, Window};
 use gpui::{div, prelude::*, rgb};
 
-pub fn guess_element(state_ticket: Entity<GameState>) -> impl IntoElement {
+pub fn guess_element(
+    state_ticket: Entity<GameState>,
+    focus_handle: &FocusHandle,
+    window: &Window,
+) -> impl IntoElement {
     div()
         .flex()
         // .flex_row()
         .items_center()
         .bg(rgb(0xf2f0ee))
         .rounded_md()
+        // フォーカスリング
+        .border_2()
+        .border_color(if focus_handle.is_focused(window) {
+            rgb(0xffc048)
+        } else {
+            rgb(0xf2f0ee)
+        })
+        .track_focus(focus_handle)
         .text_color(rgb(0x5a3e44))
         .child("GUESS!")
         .on_mouse_down(
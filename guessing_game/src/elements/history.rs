@@ -0,0 +1,42 @@
+use crate::models::game_state::{GameState, GameStatus, GuessOutcome};
+use crate::models::number_format::group;
+use crate::views::game::GameView;
+use gpui::Entity;
+use gpui::{div, prelude::*, rgb};
+
+/// これまでの予想を新しい順に並べる
+pub fn history_element(
+    state_ticket: Entity<GameState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let state = state_ticket.read(cx);
+    // 決着がついたら、どの答えが嘘だったかを明かす
+    let reveal = state.max_lies() > 0 && state.status != GameStatus::Playing;
+
+    div()
+        .id("history")
+        .flex()
+        .flex_col()
+        .w_32()
+        .h_24()
+        .overflow_y_scroll()
+        .text_sm()
+        .children(state.history.iter().enumerate().rev().map(|(i, record)| {
+            let (mark, color) = match record.outcome {
+                GuessOutcome::Correct => ("◎", rgb(0x7ed957)),
+                GuessOutcome::TooHigh => ("↓", rgb(0xff9f43)),
+                GuessOutcome::TooLow => ("↑", rgb(0x54a0ff)),
+            };
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .text_color(color)
+                .child(format!("{}.", i + 1))
+                .child(group(record.value))
+                .child(mark)
+                .when(reveal && state.is_lie(record), |this| {
+                    this.child(div().text_color(rgb(0xff6b6b)).child("嘘"))
+                })
+        }))
+}
@@ -0,0 +1,184 @@
+use crate::models::game_state::{GameState, GameStatus};
+use crate::models::hot_seat_state::{HotSeatPhase, HotSeatState};
+use crate::views::game::GameView;
+use gpui::{Entity, MouseButton};
+use gpui::{div, prelude::*, rgb};
+
+/// 出題する人が答えを入力する画面。入力は伏せ字で表示する
+pub fn hot_seat_secret_element(
+    hot_seat_ticket: Entity<HotSeatState>,
+    state_ticket: Entity<GameState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    let hot_seat = hot_seat_ticket.read(cx);
+    let range = state_ticket.read(cx).range();
+    let masked = "●".repeat(hot_seat.secret_input.chars().count());
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .child(score_line(hot_seat))
+        .child(div().text_color(rgb(0xffffff)).child(format!(
+            "{} が {} の答えを決めてね",
+            HotSeatState::player_name(hot_seat.setter),
+            range
+        )))
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .child(
+                    div()
+                        .size_24()
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .bg(rgb(0x222222))
+                        .border_2()
+                        .border_color(rgb(0x007aff))
+                        .text_color(rgb(0xffffff))
+                        .child(masked),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .bg(rgb(0xf2f0ee))
+                        .rounded_md()
+                        .px_2()
+                        .text_color(rgb(0x5a3e44))
+                        .child("決定")
+                        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                            view_ticket.update(app_cx, |view, view_cx| {
+                                view.confirm_hot_seat_secret(view_cx);
+                            })
+                        }),
+                ),
+        )
+        .child(
+            div()
+                .text_color(rgb(0xff6b6b))
+                .child(hot_seat.message.clone()),
+        )
+}
+
+/// 当てる人に端末を渡してもらう
+pub fn hot_seat_handover_element(
+    hot_seat_ticket: Entity<HotSeatState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let hot_seat = hot_seat_ticket.read(cx);
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .child(score_line(hot_seat))
+        .child(div().text_color(rgb(0xffffff)).child(format!(
+            "{} に渡してね",
+            HotSeatState::player_name(hot_seat.guesser())
+        )))
+        .child(
+            div()
+                .bg(rgb(0xf2f0ee))
+                .rounded_md()
+                .px_2()
+                .text_color(rgb(0x5a3e44))
+                .child("はじめる")
+                .on_mouse_down(MouseButton::Left, {
+                    let hot_seat_ticket = hot_seat_ticket.clone();
+                    move |_event, _window, app_cx| {
+                        hot_seat_ticket.update(app_cx, |hot_seat, model_cx| {
+                            hot_seat.set_phase(HotSeatPhase::Guessing, model_cx);
+                        })
+                    }
+                }),
+        )
+}
+
+/// 当てている間の手番と得点。決着がついたら次へ進むボタンを出す
+pub fn hot_seat_status_element(
+    hot_seat_ticket: Entity<HotSeatState>,
+    state_ticket: Entity<GameState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    let hot_seat = hot_seat_ticket.read(cx);
+    let finished = state_ticket.read(cx).status != GameStatus::Playing;
+
+    div()
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap_2()
+        .child(score_line(hot_seat))
+        .child(div().text_color(rgb(0xffffff)).child(format!(
+            "{} の番",
+            HotSeatState::player_name(hot_seat.guesser())
+        )))
+        .when(finished, |this| {
+            this.child(
+                div()
+                    .bg(rgb(0xf2f0ee))
+                    .rounded_md()
+                    .px_2()
+                    .text_color(rgb(0x5a3e44))
+                    .child("次へ")
+                    .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                        view_ticket.update(app_cx, |view, view_cx| {
+                            view.finish_hot_seat_turn(view_cx);
+                        })
+                    }),
+            )
+        })
+}
+
+/// 全ラウンドが終わったあとの結果
+pub fn hot_seat_result_element(
+    hot_seat_ticket: Entity<HotSeatState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    let hot_seat = hot_seat_ticket.read(cx);
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .text_color(rgb(0xffffff))
+        .child(div().text_xl().child(hot_seat.winner_message()))
+        .children((0..2).map(|player| {
+            format!(
+                "{}: {} 点 / 合計 {} 回",
+                HotSeatState::player_name(player),
+                hot_seat.points[player],
+                hot_seat.total_attempts[player]
+            )
+        }))
+        .child(
+            div()
+                .bg(rgb(0xf2f0ee))
+                .rounded_md()
+                .px_2()
+                .text_color(rgb(0x5a3e44))
+                .child("設定に戻る")
+                .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                    view_ticket.update(app_cx, |view, view_cx| {
+                        view.open_setup(view_cx);
+                    })
+                }),
+        )
+}
+
+fn score_line(hot_seat: &HotSeatState) -> impl IntoElement {
+    div().text_color(rgb(0xaaaaaa)).child(format!(
+        "Round {}/{}  {} - {}",
+        hot_seat.round, hot_seat.total_rounds, hot_seat.points[0], hot_seat.points[1]
+    ))
+}
@@ -0,0 +1,74 @@
+use crate::models::game_state::GameState;
+use crate::views::game::GameView;
+use gpui::{Entity, MouseButton};
+use gpui::{div, prelude::*, px, rgb};
+
+/// マウスやタッチで入力するための数字キー。キー入力と同じ GameState のメソッドを通す
+pub fn keypad_element(
+    state_ticket: Entity<GameState>,
+    show_slider: bool,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    let mut rows = vec![
+        vec!["1", "2", "3"],
+        vec!["4", "5", "6"],
+        vec!["7", "8", "9"],
+        vec!["C", "0", "⌫"],
+    ];
+    // 範囲に負の数があるときだけマイナスのキーを出す
+    if state_ticket.read(cx).range().min < 0 {
+        rows[3].insert(0, "-");
+    }
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_1()
+        .children(rows.into_iter().map(|row| {
+            div()
+                .flex()
+                .flex_row()
+                .gap_1()
+                .children(row.into_iter().map(|key| {
+                    let state_ticket = state_ticket.clone();
+                    div()
+                        .w(px(40.0))
+                        .h(px(28.0))
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .bg(rgb(0xf2f0ee))
+                        .rounded_md()
+                        .text_color(rgb(0x5a3e44))
+                        .child(key)
+                        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                            state_ticket.update(app_cx, |game, model_cx| {
+                                match key {
+                                    "C" => game.clear_input(),
+                                    "⌫" => game.pop_digit(),
+                                    digit => game.push_digit(digit),
+                                }
+                                model_cx.notify();
+                            })
+                        })
+                }))
+        }))
+        .child(
+            div()
+                .px_2()
+                .rounded_md()
+                .bg(if show_slider {
+                    rgb(0x007aff)
+                } else {
+                    rgb(0x333333)
+                })
+                .text_color(rgb(0xffffff))
+                .text_sm()
+                .child("スライダー")
+                .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                    view_ticket.update(app_cx, |view, view_cx| view.toggle_slider(view_cx))
+                }),
+        )
+}
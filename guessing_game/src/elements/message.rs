@@ -1,7 +1,7 @@
-use crate::models::game_state::GameState;
+use crate::models::game_state::{GameState, GameStatus};
 use crate::views::game::GameView;
 
-use gpui::Entity;
+use gpui::{Entity, MouseButton};
 use gpui::{div, prelude::*, rgb};
 
 pub fn message_element(
@@ -9,9 +9,35 @@ pub fn message_element(
     cx: &mut Context<GameView>,
 ) -> impl IntoElement {
     let state = state_ticket.read(cx);
-    let current_message = state.message.clone();
+    // 受け付けなかった予想は理由を出す。決着がついたら答えと一緒に色を変えて表示する
+    let (current_message, text_color) = match (state.last_error, state.status) {
+        (Some(err), _) => (err.to_string().into(), rgb(0xffc048)),
+        (None, GameStatus::Playing) => (state.message.clone(), rgb(0xffffff)),
+        (None, GameStatus::Won) => (state.message.clone(), rgb(0x7ed957)),
+        (None, GameStatus::Lost) => (state.message.clone(), rgb(0xff6b6b)),
+    };
+    let finished = state.status != GameStatus::Playing;
+    let view_ticket = cx.entity().clone();
     div()
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap_2()
         .bg(rgb(0x1e1e1e))
-        .text_color(rgb(0xffffff))
+        .text_color(text_color)
         .child(current_message)
+        // 決着がついたら振り返りを開ける
+        .when(finished, |this| {
+            this.child(
+                div()
+                    .bg(rgb(0xf2f0ee))
+                    .rounded_md()
+                    .px_2()
+                    .text_color(rgb(0x5a3e44))
+                    .child("振り返り")
+                    .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                        view_ticket.update(app_cx, |view, view_cx| view.open_coach(view_cx))
+                    }),
+            )
+        })
 }
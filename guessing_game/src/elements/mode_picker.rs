@@ -0,0 +1,63 @@
+use crate::models::setup_state::{GameKind, SetupState};
+use crate::views::game::GameView;
+use gpui::{Entity, MouseButton};
+use gpui::{div, prelude::*, px, rgb};
+
+/// タイトルの下にゲームの種類のタブと成績画面へのボタンを並べる
+pub fn mode_picker_element(
+    setup_ticket: Entity<SetupState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let current = setup_ticket.read(cx).game_kind;
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .child(
+            div()
+                .text_size(px(30.0))
+                .text_color(rgb(0xffffff))
+                .child("Guessing Game"),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .children(GameKind::ALL.map(|game_kind| {
+                    let setup_ticket = setup_ticket.clone();
+                    let selected = game_kind == current;
+                    div()
+                        .px_3()
+                        .rounded_md()
+                        .bg(if selected {
+                            rgb(0x007aff)
+                        } else {
+                            rgb(0x333333)
+                        })
+                        .text_color(rgb(0xffffff))
+                        .child(game_kind.label())
+                        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                            setup_ticket.update(app_cx, |setup, model_cx| {
+                                setup.select_game_kind(game_kind, model_cx);
+                            })
+                        })
+                }))
+                .child(
+                    div()
+                        .px_3()
+                        .rounded_md()
+                        .bg(rgb(0xf2f0ee))
+                        .text_color(rgb(0x5a3e44))
+                        .child("成績")
+                        .on_mouse_down(MouseButton::Left, {
+                            let view_ticket = cx.entity().clone();
+                            move |_event, _window, app_cx| {
+                                view_ticket.update(app_cx, |view, view_cx| view.open_stats(view_cx))
+                            }
+                        }),
+                ),
+        )
+}
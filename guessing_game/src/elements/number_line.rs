@@ -0,0 +1,65 @@
+use crate::models::game_state::GameState;
+use crate::models::number_format::group;
+use crate::views::game::GameView;
+use gpui::{Bounds, Entity, canvas, fill, point, px, size};
+use gpui::{div, prelude::*, rgb};
+
+/// 範囲全体を数直線にして、外れた部分を暗く、まだ可能性のある区間を明るく塗る
+pub fn number_line_element(
+    state_ticket: Entity<GameState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let state = state_ticket.read(cx);
+    let range = state.range();
+    let possible = state.possible_range();
+    let guesses: Vec<i64> = state.history.iter().map(|record| record.value).collect();
+
+    // 範囲の中での位置を 0.0〜1.0 にする
+    let ratio = move |num: i64| range.ratio_of(num) as f32;
+    let possible_start = ratio(possible.min);
+    let possible_end = ratio(possible.max) + (1.0 / range.size() as f64) as f32;
+
+    div()
+        .flex()
+        .flex_col()
+        .w_80()
+        .gap_1()
+        .child(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, _app| {
+                    let width = bounds.size.width;
+                    let height = bounds.size.height;
+                    window.paint_quad(fill(bounds, rgb(0x333333)).corner_radii(px(3.)));
+
+                    let start = bounds.origin.x + width * possible_start;
+                    let end = bounds.origin.x + width * possible_end;
+                    let possible_bounds = Bounds::new(
+                        point(start, bounds.origin.y),
+                        size((end - start).max(px(2.)), height),
+                    );
+                    window.paint_quad(fill(possible_bounds, rgb(0x007aff)).corner_radii(px(3.)));
+
+                    // 予想した位置に目盛りを入れる
+                    for &guess in &guesses {
+                        let x = bounds.origin.x + width * ratio(guess);
+                        let tick = Bounds::new(point(x, bounds.origin.y), size(px(1.), height));
+                        window.paint_quad(fill(tick, rgb(0xffffff)));
+                    }
+                },
+            )
+            .w_full()
+            .h(px(12.)),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .justify_between()
+                .text_xs()
+                .text_color(rgb(0xaaaaaa))
+                .child(group(range.min))
+                .child(format!("のこり {}", possible))
+                .child(group(range.max)),
+        )
+}
@@ -0,0 +1,189 @@
+use crate::models::race_state::RaceState;
+use crate::views::game::GameView;
+use gpui::{Entity, MouseButton, SharedString};
+use gpui::{div, prelude::*, px, rgb};
+
+/// 名前とアドレスを入れて、ホストするか参加するか選ぶ
+pub fn race_lobby_element(
+    race_ticket: Entity<RaceState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    let race = race_ticket.read(cx);
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .text_color(rgb(0xffffff))
+        .child(
+            div()
+                .text_color(rgb(0xaaaaaa))
+                .child(format!("LAN対戦 : {}", race.host_range))
+                .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                    view_ticket.update(app_cx, |view, view_cx| {
+                        view.open_setup(view_cx);
+                    })
+                }),
+        )
+        .child(lobby_field(
+            "名前",
+            race.name_input.clone(),
+            !race.editing_address,
+            race_ticket.clone(),
+            false,
+        ))
+        .child(lobby_field(
+            "アドレス",
+            race.address_input.clone(),
+            race.editing_address,
+            race_ticket.clone(),
+            true,
+        ))
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .child(button(
+                    "ホストする",
+                    race_ticket.clone(),
+                    |race, cx| race.host(cx),
+                ))
+                .child(button("参加する", race_ticket.clone(), |race, cx| {
+                    race.join(cx)
+                })),
+        )
+        .child(race.message.clone())
+}
+
+/// 対戦中の入力とスコアボード
+pub fn race_board_element(
+    race_ticket: Entity<RaceState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let race = race_ticket.read(cx);
+    let status = match race.range {
+        Some(range) => format!("Round {} : {}", race.round, range),
+        None => "ラウンド開始待ち".to_string(),
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .text_color(rgb(0xffffff))
+        .child(div().text_color(rgb(0xaaaaaa)).child(status))
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .child(
+                    div()
+                        .size_24()
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .bg(rgb(0x222222))
+                        .border_2()
+                        .border_color(rgb(0x007aff))
+                        .child(race.current_input.clone()),
+                )
+                .child(button("GUESS!", race_ticket.clone(), |race, cx| {
+                    race.submit_guess(cx)
+                }))
+                .when(race.is_host(), |this| {
+                    this.child(button(
+                        "次のラウンド",
+                        race_ticket.clone(),
+                        |race, cx| race.start_round(cx),
+                    ))
+                })
+                .child(button("抜ける", race_ticket.clone(), |race, cx| {
+                    race.leave(cx)
+                })),
+        )
+        .child(race.message.clone())
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .w(px(280.0))
+                .gap_1()
+                .children(race.players.iter().map(|player| {
+                    let is_me = Some(player.id) == race.my_id;
+                    let is_winner = race.winner.as_ref().is_some_and(|(id, _)| *id == player.id);
+                    div()
+                        .flex()
+                        .flex_row()
+                        .justify_between()
+                        .px_2()
+                        .rounded_md()
+                        .bg(if is_winner {
+                            rgb(0x2e5e2e)
+                        } else {
+                            rgb(0x2a2a2a)
+                        })
+                        .text_color(if is_me { rgb(0x7ec8ff) } else { rgb(0xffffff) })
+                        .child(player.name.clone())
+                        .child(format!("{}回  {}勝", player.attempts, player.wins))
+                })),
+        )
+}
+
+fn lobby_field(
+    label: &'static str,
+    value: SharedString,
+    editing: bool,
+    race_ticket: Entity<RaceState>,
+    is_address: bool,
+) -> impl IntoElement {
+    div()
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap_2()
+        .child(div().w(px(72.0)).child(label))
+        .child(
+            div()
+                .w(px(200.0))
+                .h(px(28.0))
+                .px_2()
+                .flex()
+                .items_center()
+                .bg(rgb(0x222222))
+                .border_2()
+                .border_color(if editing {
+                    rgb(0x007aff)
+                } else {
+                    rgb(0x444444)
+                })
+                .child(value)
+                .on_mouse_down(MouseButton::Left, move |_event, _window, cx| {
+                    race_ticket.update(cx, |race, model_cx| {
+                        race.select_field(is_address, model_cx);
+                    })
+                }),
+        )
+}
+
+fn button(
+    label: &'static str,
+    race_ticket: Entity<RaceState>,
+    on_click: fn(&mut RaceState, &mut Context<RaceState>),
+) -> impl IntoElement {
+    div()
+        .flex()
+        .items_center()
+        .bg(rgb(0xf2f0ee))
+        .rounded_md()
+        .px_2()
+        .text_color(rgb(0x5a3e44))
+        .child(label)
+        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+            race_ticket.update(app_cx, on_click)
+        })
+}
@@ -0,0 +1,22 @@
+use crate::models::game_state::GameState;
+use crate::views::game::GameView;
+use gpui::{Entity, MouseButton};
+use gpui::{div, prelude::*, rgb};
+
+/// 今の範囲を表示する。クリックすると設定画面に戻る
+pub fn range_element(
+    state_ticket: Entity<GameState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    let state = state_ticket.read(cx);
+
+    div()
+        .text_color(rgb(0xaaaaaa))
+        .child(format!("{} : {}", state.difficulty.label(), state.range()))
+        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+            view_ticket.update(app_cx, |view, view_cx| {
+                view.open_setup(view_cx);
+            })
+        })
+}
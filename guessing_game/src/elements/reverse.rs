@@ -0,0 +1,120 @@
+use crate::models::number_format::group;
+use crate::models::reverse_state::{Reply, ReverseState};
+use crate::views::game::GameView;
+use gpui::{Entity, MouseButton};
+use gpui::{div, prelude::*, rgb};
+
+/// コンピュータの予想と、プレイヤーが答えるボタン
+pub fn reverse_element(
+    reverse_ticket: Entity<ReverseState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    let reverse = reverse_ticket.read(cx);
+    let question = if reverse.finished {
+        format!("あなたの数は {}", group(reverse.current_guess))
+    } else {
+        format!("あなたの数は {} ？", group(reverse.current_guess))
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .child(
+            div()
+                .text_color(rgb(0xaaaaaa))
+                .child(format!("{} の数を思い浮かべてね", reverse.range))
+                .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                    view_ticket.update(app_cx, |view, view_cx| {
+                        view.open_setup(view_cx);
+                    })
+                }),
+        )
+        .child(div().text_xl().text_color(rgb(0xffffff)).child(question))
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .child(reply_button(
+                    "もっと大きい",
+                    Reply::Higher,
+                    reverse_ticket.clone(),
+                ))
+                .child(reply_button(
+                    "もっと小さい",
+                    Reply::Lower,
+                    reverse_ticket.clone(),
+                ))
+                .child(reply_button("正解", Reply::Correct, reverse_ticket.clone())),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .child(
+                    div()
+                        .bg(rgb(0xf2f0ee))
+                        .rounded_md()
+                        .px_2()
+                        .text_color(rgb(0x5a3e44))
+                        .child(format!("作戦: {}", reverse.strategy.label()))
+                        .on_mouse_down(MouseButton::Left, {
+                            let reverse_ticket = reverse_ticket.clone();
+                            move |_event, _window, app_cx| {
+                                reverse_ticket.update(app_cx, |reverse, model_cx| {
+                                    reverse.cycle_strategy(model_cx);
+                                })
+                            }
+                        }),
+                )
+                .child(
+                    div()
+                        .bg(rgb(0xf2f0ee))
+                        .rounded_md()
+                        .px_2()
+                        .text_color(rgb(0x5a3e44))
+                        .child("Reload")
+                        .on_mouse_down(MouseButton::Left, {
+                            let reverse_ticket = reverse_ticket.clone();
+                            move |_event, _window, app_cx| {
+                                reverse_ticket.update(app_cx, |reverse, model_cx| {
+                                    reverse.restart(model_cx);
+                                })
+                            }
+                        }),
+                ),
+        )
+        .child(
+            div()
+                .text_sm()
+                .text_color(rgb(0xaaaaaa))
+                .child(format!("候補 {}〜{}", reverse.low, reverse.high)),
+        )
+        .child(
+            div()
+                .text_color(rgb(0xffffff))
+                .child(reverse.message.clone()),
+        )
+}
+
+fn reply_button(
+    label: &'static str,
+    reply: Reply,
+    reverse_ticket: Entity<ReverseState>,
+) -> impl IntoElement {
+    div()
+        .bg(rgb(0xf2f0ee))
+        .rounded_md()
+        .px_2()
+        .text_color(rgb(0x5a3e44))
+        .child(label)
+        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+            reverse_ticket.update(app_cx, |reverse, model_cx| {
+                reverse.answer(reply, model_cx);
+            })
+        })
+}
@@ -0,0 +1,272 @@
+use crate::models::difficulty::Difficulty;
+use crate::models::setup_state::{PlayMode, SetupState};
+use crate::views::game::GameView;
+use gpui::{Entity, MouseButton};
+use gpui::{div, prelude::*, px, rgb};
+
+pub fn setup_element(
+    setup_ticket: Entity<SetupState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    let setup = setup_ticket.read(cx);
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .children(Difficulty::PRESETS.map(|difficulty| {
+            let view_ticket = view_ticket.clone();
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .w(px(240.0))
+                .justify_between()
+                .bg(rgb(0xf2f0ee))
+                .rounded_md()
+                .px_2()
+                .text_color(rgb(0x5a3e44))
+                .child(difficulty.label())
+                .child(difficulty.range().to_string())
+                .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                    view_ticket.update(app_cx, |view, view_cx| {
+                        view.start_game(difficulty, view_cx);
+                    })
+                })
+        }))
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .text_color(rgb(0xffffff))
+                .child(range_input(
+                    setup.min_input.clone(),
+                    !setup.editing_max,
+                    setup_ticket.clone(),
+                    false,
+                ))
+                .child("〜")
+                .child(range_input(
+                    setup.max_input.clone(),
+                    setup.editing_max,
+                    setup_ticket.clone(),
+                    true,
+                ))
+                .child(
+                    div()
+                        .bg(rgb(0xf2f0ee))
+                        .rounded_md()
+                        .px_2()
+                        .text_color(rgb(0x5a3e44))
+                        .child("Custom")
+                        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                            view_ticket.update(app_cx, |view, view_cx| {
+                                view.start_custom_game(view_cx);
+                            })
+                        }),
+                ),
+        )
+        .child(
+            div()
+                .bg(rgb(0xf2f0ee))
+                .rounded_md()
+                .px_2()
+                .text_color(rgb(0x5a3e44))
+                .child(if setup.limit_attempts {
+                    "回数制限: あり"
+                } else {
+                    "回数制限: なし"
+                })
+                .on_mouse_down(MouseButton::Left, {
+                    let setup_ticket = setup_ticket.clone();
+                    move |_event, _window, app_cx| {
+                        setup_ticket.update(app_cx, |setup, model_cx| {
+                            setup.toggle_limit_attempts(model_cx);
+                        })
+                    }
+                }),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .child(
+                    div()
+                        .bg(rgb(0xf2f0ee))
+                        .rounded_md()
+                        .px_2()
+                        .text_color(rgb(0x5a3e44))
+                        .child(format!("モード: {}", setup.mode.label()))
+                        .on_mouse_down(MouseButton::Left, {
+                            let setup_ticket = setup_ticket.clone();
+                            move |_event, _window, app_cx| {
+                                setup_ticket.update(app_cx, |setup, model_cx| {
+                                    setup.cycle_mode(model_cx);
+                                })
+                            }
+                        }),
+                )
+                .when(setup.mode == PlayMode::Solo, |this| {
+                    this.child(
+                        div()
+                            .bg(rgb(0xf2f0ee))
+                            .rounded_md()
+                            .px_2()
+                            .text_color(rgb(0x5a3e44))
+                            .child(setup.secret_kind.label())
+                            .on_mouse_down(MouseButton::Left, {
+                                let setup_ticket = setup_ticket.clone();
+                                move |_event, _window, app_cx| {
+                                    setup_ticket.update(app_cx, |setup, model_cx| {
+                                        setup.toggle_secret_kind(model_cx);
+                                    })
+                                }
+                            }),
+                    )
+                    .child(
+                        div()
+                            .bg(rgb(0xf2f0ee))
+                            .rounded_md()
+                            .px_2()
+                            .text_color(rgb(0x5a3e44))
+                            .child(format!("嘘: {}回まで", setup.lies))
+                            .on_mouse_down(MouseButton::Left, {
+                                let setup_ticket = setup_ticket.clone();
+                                move |_event, _window, app_cx| {
+                                    setup_ticket.update(app_cx, |setup, model_cx| {
+                                        setup.cycle_lies(model_cx);
+                                    })
+                                }
+                            }),
+                    )
+                })
+                .when(setup.mode == PlayMode::Solo && setup.lies > 0, |this| {
+                    this.child(
+                        div()
+                            .bg(rgb(0xf2f0ee))
+                            .rounded_md()
+                            .px_2()
+                            .text_color(rgb(0x5a3e44))
+                            .child(setup.liar.label())
+                            .on_mouse_down(MouseButton::Left, {
+                                let setup_ticket = setup_ticket.clone();
+                                move |_event, _window, app_cx| {
+                                    setup_ticket.update(app_cx, |setup, model_cx| {
+                                        setup.toggle_liar(model_cx);
+                                    })
+                                }
+                            }),
+                    )
+                })
+                .when(setup.mode == PlayMode::HotSeat, |this| {
+                    this.child(
+                        div()
+                            .bg(rgb(0xf2f0ee))
+                            .rounded_md()
+                            .px_2()
+                            .text_color(rgb(0x5a3e44))
+                            .child(format!("{} ラウンド", setup.rounds))
+                            .on_mouse_down(MouseButton::Left, {
+                                let setup_ticket = setup_ticket.clone();
+                                move |_event, _window, app_cx| {
+                                    setup_ticket.update(app_cx, |setup, model_cx| {
+                                        setup.cycle_rounds(model_cx);
+                                    })
+                                }
+                            }),
+                    )
+                })
+                .when(setup.mode == PlayMode::SpeedRun, |this| {
+                    this.child(
+                        div()
+                            .bg(rgb(0xf2f0ee))
+                            .rounded_md()
+                            .px_2()
+                            .text_color(rgb(0x5a3e44))
+                            .child(format!("{} ラウンド", setup.speed_run_rounds))
+                            .on_mouse_down(MouseButton::Left, {
+                                let setup_ticket = setup_ticket.clone();
+                                move |_event, _window, app_cx| {
+                                    setup_ticket.update(app_cx, |setup, model_cx| {
+                                        setup.cycle_speed_run_rounds(model_cx);
+                                    })
+                                }
+                            }),
+                    )
+                })
+                .when(
+                    matches!(setup.mode, PlayMode::Solo | PlayMode::SpeedRun),
+                    |this| {
+                        this.child(
+                            div()
+                                .bg(rgb(0xf2f0ee))
+                                .rounded_md()
+                                .px_2()
+                                .text_color(rgb(0x5a3e44))
+                                .child(match setup.time_limit_secs {
+                                    0 => "制限時間: なし".to_string(),
+                                    secs => format!("制限時間: {}秒", secs),
+                                })
+                                .on_mouse_down(MouseButton::Left, {
+                                    let setup_ticket = setup_ticket.clone();
+                                    move |_event, _window, app_cx| {
+                                        setup_ticket.update(app_cx, |setup, model_cx| {
+                                            setup.cycle_time_limit(model_cx);
+                                        })
+                                    }
+                                }),
+                        )
+                    },
+                ),
+        )
+        .child(
+            div()
+                .bg(rgb(0xf2f0ee))
+                .rounded_md()
+                .px_2()
+                .text_color(rgb(0x5a3e44))
+                .child("今日のチャレンジ")
+                .on_mouse_down(MouseButton::Left, {
+                    let view_ticket = cx.entity().clone();
+                    move |_event, _window, app_cx| {
+                        view_ticket.update(app_cx, |view, view_cx| {
+                            view.start_daily(view_cx);
+                        })
+                    }
+                }),
+        )
+        .child(div().text_color(rgb(0xffffff)).child(setup.message.clone()))
+}
+
+fn range_input(
+    value: gpui::SharedString,
+    editing: bool,
+    setup_ticket: Entity<SetupState>,
+    is_max: bool,
+) -> impl IntoElement {
+    div()
+        .w(px(96.0))
+        .h(px(28.0))
+        .flex()
+        .items_center()
+        .justify_center()
+        .bg(rgb(0x222222))
+        .border_2()
+        .border_color(if editing {
+            rgb(0x007aff)
+        } else {
+            rgb(0x444444)
+        })
+        .text_color(rgb(0xffffff))
+        .child(value)
+        .on_mouse_down(MouseButton::Left, move |_event, _window, cx| {
+            setup_ticket.update(cx, |setup, model_cx| {
+                setup.select_field(is_max, model_cx);
+            })
+        })
+}
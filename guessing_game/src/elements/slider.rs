@@ -0,0 +1,95 @@
+use crate::models::game_state::GameState;
+use crate::models::number_format::{group, parse_grouped};
+use crate::views::game::GameView;
+use gpui::{Bounds, Entity, MouseButton, Pixels, Point, canvas, fill, point, px, size};
+use gpui::{div, prelude::*, rgb};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// まだ可能性のある範囲だけを端から端にとったスライダー。ドラッグで入力を選ぶ
+pub fn slider_element(
+    state_ticket: Entity<GameState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let state = state_ticket.read(cx);
+    let possible = state.possible_range();
+    let value = parse_grouped(&state.current_input).filter(|num| possible.contains(*num));
+    let span = (possible.max as i128 - possible.min as i128).max(1) as f64;
+    let thumb_ratio = value.map(|num| ((num as i128 - possible.min as i128) as f64 / span) as f32);
+    // 描いたときの位置を覚えておき、マウスの位置を数に直すのに使う
+    let track_bounds: Rc<Cell<Bounds<Pixels>>> = Rc::new(Cell::new(Bounds::default()));
+
+    let pick = {
+        let track_bounds = track_bounds.clone();
+        move |position: Point<Pixels>, app_cx: &mut gpui::App| {
+            let bounds = track_bounds.get();
+            if bounds.size.width <= px(0.) {
+                return;
+            }
+            let ratio = ((position.x - bounds.origin.x) / bounds.size.width).clamp(0.0, 1.0);
+            let num = possible.at_ratio(ratio as f64);
+            state_ticket.update(app_cx, |game, model_cx| {
+                game.set_input(num);
+                model_cx.notify();
+            })
+        }
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .w_80()
+        .gap_1()
+        .child(
+            div()
+                .w_full()
+                .h(px(20.))
+                .child(
+                    canvas(
+                        {
+                            let track_bounds = track_bounds.clone();
+                            move |bounds, _, _| track_bounds.set(bounds)
+                        },
+                        move |bounds, _, window, _app| {
+                            let track = Bounds::new(
+                                point(
+                                    bounds.origin.x,
+                                    bounds.origin.y + bounds.size.height / 2. - px(2.),
+                                ),
+                                size(bounds.size.width, px(4.)),
+                            );
+                            window.paint_quad(fill(track, rgb(0x333333)).corner_radii(px(2.)));
+                            if let Some(ratio) = thumb_ratio {
+                                let x = bounds.origin.x + bounds.size.width * ratio;
+                                let thumb = Bounds::new(
+                                    point(x - px(6.), bounds.origin.y),
+                                    size(px(12.), bounds.size.height),
+                                );
+                                window.paint_quad(fill(thumb, rgb(0x007aff)).corner_radii(px(6.)));
+                            }
+                        },
+                    )
+                    .size_full(),
+                )
+                .on_mouse_down(MouseButton::Left, {
+                    let pick = pick.clone();
+                    move |event, _window, app_cx| pick(event.position, app_cx)
+                })
+                .on_mouse_move(move |event, _window, app_cx| {
+                    if event.pressed_button == Some(MouseButton::Left) {
+                        pick(event.position, app_cx);
+                    }
+                }),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .justify_between()
+                .text_xs()
+                .text_color(rgb(0xaaaaaa))
+                .child(group(possible.min))
+                .child(value.map_or("-".to_string(), group))
+                .child(group(possible.max)),
+        )
+}
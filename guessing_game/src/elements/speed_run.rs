@@ -0,0 +1,53 @@
+use crate::elements::timer::format_duration;
+use crate::models::speed_run_state::{GUESS_PENALTY_SECS, SpeedRunState};
+use crate::views::game::GameView;
+use gpui::Entity;
+use gpui::{div, prelude::*, rgb};
+
+/// スピードランの各ラウンドの結果。全部終わったら合計のタイムを出す
+pub fn speed_run_element(
+    speed_run_ticket: Entity<SpeedRunState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let speed_run = speed_run_ticket.read(cx);
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_1()
+        .text_sm()
+        .text_color(rgb(0xaaaaaa))
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .children(speed_run.results.iter().enumerate().map(|(i, round)| {
+                    div()
+                        .text_color(if round.won {
+                            rgb(0x7ed957)
+                        } else {
+                            rgb(0xff6b6b)
+                        })
+                        .child(format!(
+                            "R{} {}回 {}",
+                            i + 1,
+                            round.attempts,
+                            format_duration(round.elapsed)
+                        ))
+                })),
+        )
+        .when(speed_run.is_complete(), |this| {
+            this.child(div().text_color(rgb(0xffffff)).child(format!(
+                "完走！ {} / 予想 {}回  タイム {} (1回{}秒加算)",
+                format_duration(speed_run.elapsed()),
+                speed_run.total_attempts(),
+                format_duration(speed_run.score()),
+                GUESS_PENALTY_SECS
+            )))
+        })
+        .when(!speed_run.is_complete(), |this| {
+            this.child("ラウンドが終わったら Reload で次へ")
+        })
+}
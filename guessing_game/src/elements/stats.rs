@@ -0,0 +1,208 @@
+use crate::models::stats::{LEADERBOARD_SIZE, StatsState};
+use crate::views::game::GameView;
+use gpui::{Entity, MouseButton};
+use gpui::{div, prelude::*, px, rgb};
+
+/// 成績画面。ランキングと通算成績を並べる
+pub fn stats_element(
+    stats_ticket: Entity<StatsState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    // 名前の入力を待っていなければ空になる
+    let name_entry = name_entry_element(stats_ticket.clone(), cx).into_any_element();
+    let stats = stats_ticket.read(cx);
+    let lifetime = &stats.file.lifetime;
+    let entries = stats.leaderboard(&stats.board).to_vec();
+    // 一番多い回数を棒の長さの基準にする
+    let peak = lifetime
+        .histogram
+        .values()
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_4()
+        .text_color(rgb(0xffffff))
+        .child(name_entry)
+        .child(div().flex().flex_row().flex_wrap().gap_2().children(
+            stats.boards().into_iter().map(|board| {
+                let stats_ticket = stats_ticket.clone();
+                let selected = board == stats.board;
+                div()
+                    .px_3()
+                    .rounded_md()
+                    .bg(if selected {
+                        rgb(0x007aff)
+                    } else {
+                        rgb(0x333333)
+                    })
+                    .child(board.clone())
+                    .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                        stats_ticket.update(app_cx, |stats, model_cx| {
+                            stats.select_board(board.clone(), model_cx);
+                        })
+                    })
+            }),
+        ))
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .text_sm()
+                .when(entries.is_empty(), |this| {
+                    this.child(div().text_color(rgb(0xaaaaaa)).child("まだ記録がないよ"))
+                })
+                .children(entries.iter().enumerate().map(|(i, entry)| {
+                    div()
+                        .flex()
+                        .flex_row()
+                        .gap_2()
+                        .child(div().w_8().child(format!("{}.", i + 1)))
+                        .child(div().w_32().child(entry.name.clone()))
+                        .child(div().w_16().child(format!("{}回", entry.attempts)))
+                        .child(
+                            div()
+                                .w_16()
+                                .child(format!("{:.1}秒", entry.millis as f64 / 1000.0)),
+                        )
+                        .child(
+                            div()
+                                .text_color(rgb(0xaaaaaa))
+                                .child(entry.date.to_string()),
+                        )
+                })),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .items_center()
+                .gap_1()
+                .child(format!(
+                    "通算 {} ゲーム  勝率 {:.0}%  平均 {}",
+                    lifetime.played,
+                    lifetime.win_rate() * 100.0,
+                    lifetime
+                        .average_attempts()
+                        .map_or("-".to_string(), |average| format!("{:.1}回", average))
+                ))
+                .children(lifetime.histogram.iter().map(|(&attempts, &count)| {
+                    div()
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap_2()
+                        .text_sm()
+                        .child(div().w_8().child(attempts.to_string()))
+                        .child(
+                            div()
+                                .w(px(200.0 * count as f32 / peak as f32))
+                                .h(px(10.0))
+                                .bg(rgb(0x007aff)),
+                        )
+                        .child(count.to_string())
+                })),
+        )
+        .child(div().text_color(rgb(0xffc048)).child(stats.message.clone()))
+        .child(
+            div()
+                .bg(rgb(0xf2f0ee))
+                .rounded_md()
+                .px_2()
+                .text_color(rgb(0x5a3e44))
+                .child("戻る")
+                .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                    view_ticket.update(app_cx, |view, view_cx| view.open_setup(view_cx))
+                }),
+        )
+}
+
+/// ランキングに入った記録の名前を入力する
+pub fn name_entry_element(
+    stats_ticket: Entity<StatsState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let stats = stats_ticket.read(cx);
+    let Some(pending) = stats.pending.clone() else {
+        return div();
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .child(format!(
+            "{} のトップ{}入り！ {}回 {:.1}秒",
+            pending.board,
+            LEADERBOARD_SIZE,
+            pending.attempts,
+            pending.millis as f64 / 1000.0
+        ))
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child("名前")
+                .child(
+                    div()
+                        .w(px(160.0))
+                        .h(px(28.0))
+                        .px_2()
+                        .flex()
+                        .items_center()
+                        .bg(rgb(0x222222))
+                        .border_2()
+                        .border_color(rgb(0x007aff))
+                        .child(stats.name_input.clone()),
+                )
+                .child(button(
+                    "登録",
+                    stats_ticket.clone(),
+                    StatsState::submit_name,
+                ))
+                .child(button(
+                    "登録しない",
+                    stats_ticket.clone(),
+                    StatsState::skip_name,
+                )),
+        )
+}
+
+/// ゲーム画面に出す、ランキング入りの知らせ
+pub fn high_score_banner_element(cx: &mut Context<GameView>) -> impl IntoElement {
+    let view_ticket = cx.entity().clone();
+    div()
+        .bg(rgb(0xffc048))
+        .rounded_md()
+        .px_2()
+        .text_color(rgb(0x5a3e44))
+        .child("ランキング入り！ 名前を登録する")
+        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+            view_ticket.update(app_cx, |view, view_cx| view.open_stats(view_cx))
+        })
+}
+
+fn button(
+    label: &'static str,
+    stats_ticket: Entity<StatsState>,
+    on_click: fn(&mut StatsState, &mut Context<StatsState>),
+) -> impl IntoElement {
+    div()
+        .bg(rgb(0xf2f0ee))
+        .rounded_md()
+        .px_2()
+        .text_color(rgb(0x5a3e44))
+        .child(label)
+        .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+            stats_ticket.update(app_cx, on_click)
+        })
+}
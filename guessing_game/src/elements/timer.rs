@@ -0,0 +1,49 @@
+use crate::models::game_state::{GameState, GameStatus};
+use crate::models::speed_run_state::SpeedRunState;
+use crate::views::game::GameView;
+use gpui::Entity;
+use gpui::{div, prelude::*, rgb};
+use std::time::Duration;
+
+/// 見出しの横に出す時計。スピードラン中は通しの時間、制限時間があれば残りも出す
+pub fn timer_element(
+    state_ticket: Entity<GameState>,
+    speed_run_ticket: Entity<SpeedRunState>,
+    cx: &mut Context<GameView>,
+) -> impl IntoElement {
+    let state = state_ticket.read(cx);
+    let speed_run = speed_run_ticket.read(cx);
+    let clock = if speed_run.active {
+        format!(
+            "⏱ {}  {}/{}",
+            format_duration(speed_run.elapsed()),
+            speed_run.current_round(),
+            speed_run.rounds
+        )
+    } else {
+        format!("⏱ {}", format_duration(state.elapsed()))
+    };
+    let remaining = state
+        .remaining()
+        .filter(|_| state.status == GameStatus::Playing);
+    // 残り10秒を切ったら赤くする
+    let hurry = remaining.is_some_and(|remaining| remaining < Duration::from_secs(10));
+
+    div()
+        .flex()
+        .flex_row()
+        .gap_2()
+        .text_color(rgb(0xaaaaaa))
+        .child(clock)
+        .children(remaining.map(|remaining| {
+            div()
+                .text_color(if hurry { rgb(0xff6b6b) } else { rgb(0xffffff) })
+                .child(format!("残り {}", format_duration(remaining)))
+        }))
+}
+
+/// 分:秒.1/10秒
+pub fn format_duration(duration: Duration) -> String {
+    let tenths = duration.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
@@ -5,5 +5,5 @@ pub fn title_element() -> impl IntoElement {
         .text_size(px(30.0))
         .bg(rgb(0x1e1e1e))
         .text_color(rgb(0xffffff))
-        .child(format!("Guessing Game"))
+        .child("Guessing Game")
 }
@@ -0,0 +1,127 @@
+use crate::storage;
+use gpui::{App, KeyBinding, Keystroke, actions};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+actions!(
+    guessing_game,
+    [
+        Submit, Reload, ClearInput, Increment, Decrement, FocusNext, FocusPrev
+    ]
+);
+
+actions!(
+    number_input,
+    [
+        Backspace,
+        Delete,
+        Left,
+        Right,
+        SelectLeft,
+        SelectRight,
+        SelectAll,
+        Home,
+        End,
+        Paste,
+        Copy,
+        Cut
+    ]
+);
+
+/// ゲーム画面のルートに付けるキーコンテキスト
+pub const KEY_CONTEXT: &str = "Game";
+/// 数の入力欄のキーコンテキスト
+pub const INPUT_KEY_CONTEXT: &str = "NumberInput";
+
+/// keymap.json で使うアクション名と既定のキー
+const DEFAULT_KEYMAP: [(&str, &[&str]); 19] = [
+    ("submit", &["enter"]),
+    ("reload", &["ctrl-r", "f5"]),
+    ("clear_input", &["escape"]),
+    ("increment", &["up"]),
+    ("decrement", &["down"]),
+    ("focus_next", &["tab"]),
+    ("focus_prev", &["shift-tab"]),
+    ("backspace", &["backspace"]),
+    ("delete", &["delete"]),
+    ("left", &["left"]),
+    ("right", &["right"]),
+    ("select_left", &["shift-left"]),
+    ("select_right", &["shift-right"]),
+    ("select_all", &["secondary-a"]),
+    ("home", &["home"]),
+    ("end", &["end"]),
+    ("paste", &["secondary-v"]),
+    ("copy", &["secondary-c"]),
+    ("cut", &["secondary-x"]),
+];
+
+/// $XDG_CONFIG_HOME/guessing_game/keymap.json (なければ ~/.config/guessing_game/keymap.json)
+/// 例: { "reload": ["ctrl-n"], "submit": ["enter", "space"] }
+pub fn keymap_path() -> Option<PathBuf> {
+    storage::xdg_path("XDG_CONFIG_HOME", ".config", "keymap.json")
+}
+
+fn load_user_keymap() -> Result<HashMap<String, Vec<String>>, String> {
+    let Some(path) = keymap_path() else {
+        return Ok(HashMap::new());
+    };
+    storage::load_json(&path).map(Option::unwrap_or_default)
+}
+
+fn binding(name: &str, keystrokes: &str) -> Option<KeyBinding> {
+    // KeyBinding::new は解釈できないキーでpanicするので先に確かめる
+    if keystrokes
+        .split_whitespace()
+        .any(|keystroke| Keystroke::parse(keystroke).is_err())
+    {
+        eprintln!("keymap: {} のキー {:?} が解釈できない", name, keystrokes);
+        return None;
+    }
+    let context = Some(KEY_CONTEXT);
+    let input = Some(INPUT_KEY_CONTEXT);
+    Some(match name {
+        "submit" => KeyBinding::new(keystrokes, Submit, context),
+        "reload" => KeyBinding::new(keystrokes, Reload, context),
+        "clear_input" => KeyBinding::new(keystrokes, ClearInput, context),
+        "increment" => KeyBinding::new(keystrokes, Increment, context),
+        "decrement" => KeyBinding::new(keystrokes, Decrement, context),
+        "focus_next" => KeyBinding::new(keystrokes, FocusNext, context),
+        "focus_prev" => KeyBinding::new(keystrokes, FocusPrev, context),
+        "backspace" => KeyBinding::new(keystrokes, Backspace, input),
+        "delete" => KeyBinding::new(keystrokes, Delete, input),
+        "left" => KeyBinding::new(keystrokes, Left, input),
+        "right" => KeyBinding::new(keystrokes, Right, input),
+        "select_left" => KeyBinding::new(keystrokes, SelectLeft, input),
+        "select_right" => KeyBinding::new(keystrokes, SelectRight, input),
+        "select_all" => KeyBinding::new(keystrokes, SelectAll, input),
+        "home" => KeyBinding::new(keystrokes, Home, input),
+        "end" => KeyBinding::new(keystrokes, End, input),
+        "paste" => KeyBinding::new(keystrokes, Paste, input),
+        "copy" => KeyBinding::new(keystrokes, Copy, input),
+        "cut" => KeyBinding::new(keystrokes, Cut, input),
+        _ => return None,
+    })
+}
+
+/// 既定のキーを keymap.json に書いたアクションだけ置き換えて登録する
+pub fn bind_keymap(cx: &mut App) {
+    let mut user_keymap = load_user_keymap().unwrap_or_else(|err| {
+        eprintln!("keymap.json の読み込みに失敗: {}", err);
+        HashMap::new()
+    });
+    for name in user_keymap.keys() {
+        if !DEFAULT_KEYMAP.iter().any(|(known, _)| known == name) {
+            eprintln!("keymap: 知らないアクション {}", name);
+        }
+    }
+
+    let mut bindings = Vec::new();
+    for (name, default_keys) in DEFAULT_KEYMAP {
+        let keys = user_keymap
+            .remove(name)
+            .unwrap_or_else(|| default_keys.iter().map(|key| key.to_string()).collect());
+        bindings.extend(keys.iter().filter_map(|key| binding(name, key)));
+    }
+    cx.bind_keys(bindings);
+}
@@ -1,3 +1,6 @@
+pub mod cli;
 pub mod elements;
+pub mod keymap;
 pub mod models;
+pub mod storage;
 pub mod views;
@@ -1,12 +1,20 @@
 use gpui::*;
 use views::game::GameView;
 
+mod cli;
 mod elements;
+mod keymap;
 mod models;
+mod storage;
 mod views;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
     Application::new().run(|app_cx: &mut App| {
+        keymap::bind_keymap(app_cx);
         app_cx
             .open_window(
                 // crate::WindowOptions
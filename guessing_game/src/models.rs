@@ -1 +1,18 @@
+pub mod bulls_cows_state;
+pub mod coach;
+pub mod daily;
+pub mod difficulty;
+pub mod effects_state;
 pub mod game_state;
+pub mod hot_seat_state;
+pub mod number_format;
+pub mod oracle;
+pub mod race_net;
+pub mod race_state;
+pub mod reverse_state;
+pub mod secret;
+pub mod setup_state;
+pub mod simulator;
+pub mod speed_run_state;
+pub mod stats;
+pub mod strategy;
@@ -0,0 +1,236 @@
+use crate::models::game_state::GameStatus;
+use gpui::Context;
+use gpui::SharedString;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use std::fmt;
+
+pub const CODE_LENGTHS: [usize; 4] = [3, 4, 5, 6];
+pub const CODE_ATTEMPTS: [Option<u32>; 4] = [Some(8), Some(10), Some(12), None];
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct BullsCowsSettings {
+    pub length: usize,
+    pub allow_repeats: bool,
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for BullsCowsSettings {
+    fn default() -> Self {
+        BullsCowsSettings {
+            length: 4,
+            allow_repeats: false,
+            max_attempts: Some(10),
+        }
+    }
+}
+
+/// 1回分の予想と、位置も数字も合っている数(bulls)、数字だけ合っている数(cows)
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CodeRecord {
+    pub guess: Vec<u8>,
+    pub bulls: usize,
+    pub cows: usize,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CodeError {
+    WrongLength(usize),
+    RepeatedDigit,
+    GameOver,
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeError::WrongLength(length) => write!(f, "{}桁で入力してね", length),
+            CodeError::RepeatedDigit => write!(f, "同じ数字は使えないよ"),
+            CodeError::GameOver => write!(f, "決着がついたよ。Reloadで次のゲームへ"),
+        }
+    }
+}
+
+pub struct BullsCowsState {
+    secret: Vec<u8>,
+    pub settings: BullsCowsSettings,
+    pub status: GameStatus,
+    pub history: Vec<CodeRecord>,
+    pub current_input: SharedString,
+    pub message: SharedString,
+}
+
+impl BullsCowsState {
+    pub fn new(settings: BullsCowsSettings) -> Self {
+        BullsCowsState {
+            secret: Self::new_secret(settings),
+            settings,
+            status: GameStatus::Playing,
+            history: Vec::new(),
+            current_input: String::new().into(),
+            message: String::new().into(),
+        }
+    }
+
+    fn new_secret(settings: BullsCowsSettings) -> Vec<u8> {
+        let mut rng = rand::rng();
+        if settings.allow_repeats {
+            (0..settings.length)
+                .map(|_| rng.random_range(0..=9))
+                .collect()
+        } else {
+            let mut digits: Vec<u8> = (0..=9).collect();
+            digits.shuffle(&mut rng);
+            digits.truncate(settings.length);
+            digits
+        }
+    }
+
+    pub fn start(&mut self, settings: BullsCowsSettings, cx: &mut Context<Self>) {
+        *self = BullsCowsState::new(settings);
+        cx.notify();
+    }
+
+    pub fn reload(&mut self, cx: &mut Context<Self>) {
+        self.start(self.settings, cx);
+    }
+
+    pub fn push_digit(&mut self, key: &str, cx: &mut Context<Self>) {
+        if self.current_input.len() < self.settings.length {
+            self.current_input = format!("{}{}", self.current_input, key).into();
+        }
+        cx.notify();
+    }
+
+    pub fn pop_digit(&mut self, cx: &mut Context<Self>) {
+        let mut chars = self.current_input.chars();
+        chars.next_back();
+        self.current_input = chars.as_str().to_string().into();
+        cx.notify();
+    }
+
+    /// 重複ありのときも、数字ごとに少ない方の個数だけ cows として数える
+    pub fn score(secret: &[u8], guess: &[u8]) -> (usize, usize) {
+        let bulls = secret.iter().zip(guess).filter(|(s, g)| s == g).count();
+        let common: usize = (0..=9)
+            .map(|digit| {
+                let in_secret = secret.iter().filter(|&&d| d == digit).count();
+                let in_guess = guess.iter().filter(|&&d| d == digit).count();
+                in_secret.min(in_guess)
+            })
+            .sum();
+        (bulls, common - bulls)
+    }
+
+    pub fn submit(&mut self, input: &str) -> Result<(usize, usize), CodeError> {
+        if self.status != GameStatus::Playing {
+            return Err(CodeError::GameOver);
+        }
+        let guess: Vec<u8> = input
+            .chars()
+            .filter_map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect();
+        if guess.len() != self.settings.length || input.chars().count() != guess.len() {
+            return Err(CodeError::WrongLength(self.settings.length));
+        }
+        if !self.settings.allow_repeats
+            && (0..guess.len()).any(|i| guess[i + 1..].contains(&guess[i]))
+        {
+            return Err(CodeError::RepeatedDigit);
+        }
+
+        let (bulls, cows) = Self::score(&self.secret, &guess);
+        self.history.push(CodeRecord { guess, bulls, cows });
+        let attempts = self.history.len() as u32;
+        if bulls == self.settings.length {
+            self.status = GameStatus::Won;
+            self.message = format!("正解！ {}回で当てた", attempts).into();
+        } else if self
+            .settings
+            .max_attempts
+            .is_some_and(|max| attempts >= max)
+        {
+            self.status = GameStatus::Lost;
+            let secret: String = self.secret.iter().map(|d| d.to_string()).collect();
+            self.message = format!("残念… 答えは {}", secret).into();
+        } else {
+            self.message = format!("{} Bulls / {} Cows", bulls, cows).into();
+        }
+        Ok((bulls, cows))
+    }
+
+    pub fn sub_guess(&mut self, cx: &mut Context<Self>) {
+        let input = self.current_input.clone();
+        match self.submit(&input) {
+            Ok(_) => self.current_input = String::new().into(),
+            Err(err) => self.message = err.to_string().into(),
+        }
+        cx.notify();
+    }
+}
+
+impl Default for BullsCowsState {
+    fn default() -> Self {
+        Self::new(BullsCowsSettings::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_counts_bulls_and_cows() {
+        assert_eq!(BullsCowsState::score(&[1, 2, 3, 4], &[1, 2, 3, 4]), (4, 0));
+        assert_eq!(BullsCowsState::score(&[1, 2, 3, 4], &[4, 3, 2, 1]), (0, 4));
+        assert_eq!(BullsCowsState::score(&[1, 2, 3, 4], &[1, 5, 6, 2]), (1, 1));
+        assert_eq!(BullsCowsState::score(&[1, 2, 3, 4], &[5, 6, 7, 8]), (0, 0));
+    }
+
+    #[test]
+    fn score_with_repeats_counts_each_digit_at_most_once() {
+        // 1 は答えに2つしかないので、予想の3つ目の1は数えない
+        assert_eq!(BullsCowsState::score(&[1, 1, 2, 3], &[1, 2, 1, 1]), (1, 2));
+        assert_eq!(BullsCowsState::score(&[1, 1, 2, 2], &[3, 3, 1, 1]), (0, 2));
+        assert_eq!(BullsCowsState::score(&[7, 7, 7, 7], &[7, 0, 0, 0]), (1, 0));
+        assert_eq!(BullsCowsState::score(&[0, 0, 0, 0], &[0, 0, 0, 0]), (4, 0));
+    }
+
+    #[test]
+    fn submit_validates_and_ends_the_game() {
+        let settings = BullsCowsSettings {
+            length: 4,
+            allow_repeats: false,
+            max_attempts: Some(2),
+        };
+        let mut state = BullsCowsState::new(settings);
+        state.secret = vec![1, 2, 3, 4];
+        assert_eq!(state.submit("123"), Err(CodeError::WrongLength(4)));
+        assert_eq!(state.submit("12a4"), Err(CodeError::WrongLength(4)));
+        assert_eq!(state.submit("1123"), Err(CodeError::RepeatedDigit));
+        assert_eq!(state.submit("4321"), Ok((0, 4)));
+        assert_eq!(state.submit("5678"), Ok((0, 0)));
+        assert_eq!(state.status, GameStatus::Lost);
+        assert_eq!(state.submit("1234"), Err(CodeError::GameOver));
+
+        let mut state = BullsCowsState::new(settings);
+        state.secret = vec![1, 2, 3, 4];
+        assert_eq!(state.submit("1234"), Ok((4, 0)));
+        assert_eq!(state.status, GameStatus::Won);
+    }
+
+    #[test]
+    fn secret_follows_the_settings() {
+        for length in CODE_LENGTHS {
+            let state = BullsCowsState::new(BullsCowsSettings {
+                length,
+                allow_repeats: false,
+                max_attempts: None,
+            });
+            assert_eq!(state.secret.len(), length);
+            let mut digits = state.secret.clone();
+            digits.sort_unstable();
+            digits.dedup();
+            assert_eq!(digits.len(), length);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::models::difficulty::NumberRange;
+use crate::models::game_state::{GameState, GuessOutcome};
+
+/// 1回の予想の良さ
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Rating {
+    Best,
+    Good,
+    Fair,
+    Wasted,
+}
+
+impl Rating {
+    pub fn from_efficiency(efficiency: f64) -> Self {
+        if efficiency >= 0.95 {
+            Rating::Best
+        } else if efficiency >= 0.75 {
+            Rating::Good
+        } else if efficiency >= 0.4 {
+            Rating::Fair
+        } else {
+            Rating::Wasted
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Rating::Best => "最善",
+            Rating::Good => "良い",
+            Rating::Fair => "まずまず",
+            Rating::Wasted => "もったいない",
+        }
+    }
+}
+
+/// 1回分の予想の振り返り
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct GuessAnalysis {
+    pub value: i64,
+    pub outcome: GuessOutcome,
+    /// 予想する前にまだ答えの可能性があった範囲
+    pub possible: NumberRange,
+    /// その時点での二分探索の予想
+    pub optimal: i64,
+    /// 実際の結果で絞り込めた情報量(ビット)
+    pub bits: f64,
+    /// 最悪の結果でも得られる情報量を、二分探索と比べた割合(0〜1)
+    pub efficiency: f64,
+    pub rating: Rating,
+}
+
+/// 決着がついたゲームの振り返り
+#[derive(PartialEq, Clone, Debug)]
+pub struct CoachReport {
+    pub guesses: Vec<GuessAnalysis>,
+    pub total: u32,
+    /// 二分探索なら必ず当てられる回数
+    pub minimum: u32,
+    /// 答えを特定するのに必要な情報量(ビット)
+    pub bits_needed: f64,
+}
+
+impl CoachReport {
+    pub fn analyze(game: &GameState) -> Self {
+        let range = game.range();
+        let guesses = game
+            .history
+            .iter()
+            .enumerate()
+            .map(|(index, record)| {
+                let possible = game.possible_before(index);
+                let after = game.possible_before(index + 1);
+                let efficiency = worst_case_bits(possible, record.value)
+                    / worst_case_bits(possible, possible.midpoint());
+                let efficiency = if efficiency.is_nan() { 1.0 } else { efficiency };
+                GuessAnalysis {
+                    value: record.value,
+                    outcome: record.outcome,
+                    possible,
+                    optimal: possible.midpoint(),
+                    bits: (possible.size() as f64 / after.size() as f64).log2(),
+                    efficiency,
+                    rating: Rating::from_efficiency(efficiency),
+                }
+            })
+            .collect();
+        CoachReport {
+            guesses,
+            total: game.attempts,
+            minimum: range.optimal_attempts(),
+            bits_needed: (range.size() as f64).log2(),
+        }
+    }
+
+    /// 全体の効率。予想ごとの割合の平均
+    pub fn efficiency(&self) -> f64 {
+        if self.guesses.is_empty() {
+            return 0.0;
+        }
+        self.guesses
+            .iter()
+            .map(|guess| guess.efficiency)
+            .sum::<f64>()
+            / self.guesses.len() as f64
+    }
+}
+
+/// 外れたときに残る範囲が一番大きくなる場合の情報量。
+/// 範囲の外を予想すると何も絞り込めない。1つに絞れていれば無限大
+fn worst_case_bits(possible: NumberRange, guess: i64) -> f64 {
+    let remaining = if possible.contains(guess) {
+        (guess as i128 - possible.min as i128).max(possible.max as i128 - guess as i128) as u128
+    } else {
+        possible.size()
+    };
+    (possible.size() as f64 / remaining as f64).log2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::difficulty::Difficulty;
+    use crate::models::game_state::{GameStatus, GuessRecord};
+    use crate::models::oracle::LiarKind;
+
+    /// 1〜100で答えを決めて、予想を順に出したゲーム
+    fn played(secret: i64, guesses: impl IntoIterator<Item = i64>) -> GameState {
+        let mut game = GameState::new(Difficulty::Normal);
+        assert!(game.reload_with_secret(secret));
+        for guess in guesses {
+            assert!(game.submit(&guess.to_string()).is_ok());
+        }
+        game
+    }
+
+    #[test]
+    fn bisection_rates_every_guess_best() {
+        // 1〜100で答えが1なら、真ん中を選び続けると 50, 25, 12, 6, 3, 1
+        let game = played(1, [50, 25, 12, 6, 3, 1]);
+        assert_eq!(game.status, GameStatus::Won);
+        let report = CoachReport::analyze(&game);
+        assert_eq!(report.guesses.len(), 6);
+        for guess in &report.guesses {
+            assert_eq!(guess.value, guess.optimal);
+            assert_eq!(guess.rating, Rating::Best);
+        }
+        assert!(report.efficiency() > 0.99);
+        assert!(report.total <= report.minimum);
+    }
+
+    #[test]
+    fn linear_scan_rates_poorly() {
+        let game = played(8, 1..=8);
+        assert_eq!(game.status, GameStatus::Won);
+        let report = CoachReport::analyze(&game);
+        assert_eq!(report.guesses[0].rating, Rating::Wasted);
+        assert_eq!(report.guesses[0].optimal, 50);
+        assert!(
+            report
+                .guesses
+                .iter()
+                .take(7)
+                .all(|guess| guess.rating == Rating::Wasted)
+        );
+        assert!(report.efficiency() < 0.4);
+        assert!(report.total > report.minimum);
+    }
+
+    #[test]
+    fn liar_game_keeps_the_range_wide_and_rates_the_choice() {
+        let mut game = GameState::new(Difficulty::Normal);
+        game.set_oracle(LiarKind::Random.build(1));
+        assert!(game.reload_with_secret(30));
+        // 答えは30。2回目の「大きい」は嘘
+        for (value, outcome) in [
+            (50, GuessOutcome::TooHigh),
+            (25, GuessOutcome::TooHigh),
+            (37, GuessOutcome::TooHigh),
+        ] {
+            game.history.push(GuessRecord { value, outcome });
+        }
+        let report = CoachReport::analyze(&game);
+        // 嘘が1回まで許されるので、1回の答えだけでは何も絞れない
+        let first = report.guesses[0];
+        assert_eq!(first.bits, 0.0);
+        assert_eq!(first.rating, Rating::Best);
+        assert_eq!(report.guesses[1].possible, game.range());
+        // 2回続けて「大きい」なら、50より上は嘘が2回になるので外れる
+        assert_eq!(report.guesses[2].possible, NumberRange::new(1, 49).unwrap());
+        assert_eq!(report.guesses[2].optimal, 25);
+        assert_ne!(report.guesses[2].rating, Rating::Best);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::models::difficulty::{Difficulty, NumberRange};
+use crate::models::game_state::{GameState, GameStatus, GuessOutcome};
+use crate::storage;
+use chrono::{Datelike, Local, NaiveDate};
+use gpui::{Context, SharedString};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// デイリーチャレンジはみんな同じ範囲で遊ぶ
+pub const DAILY_DIFFICULTY: Difficulty = Difficulty::Normal;
+
+/// 1日分の結果
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct DailyRecord {
+    pub date: NaiveDate,
+    pub won: bool,
+    pub attempts: u32,
+    pub share_text: String,
+    /// 答えを出した時点で記録するので、決着がつくまでは false
+    pub finished: bool,
+}
+
+pub struct DailyState {
+    pub records: Vec<DailyRecord>,
+    pub today: NaiveDate,
+    /// いま遊んでいるゲームがデイリーかどうか
+    pub active: bool,
+    pub message: SharedString,
+}
+
+impl DailyState {
+    pub fn new() -> Self {
+        let (mut records, message) = match Self::load() {
+            Ok(records) => (records.unwrap_or_default(), String::new()),
+            Err(err) => (
+                Vec::new(),
+                format!("デイリーの記録の読み込みに失敗: {}", err),
+            ),
+        };
+        // 前回遊んでいる途中で閉じたデイリーは、やめたものとして扱う
+        for record in &mut records {
+            record.finished = true;
+        }
+        DailyState {
+            records,
+            today: Local::now().date_naive(),
+            active: false,
+            message: message.into(),
+        }
+    }
+
+    /// 日付から決まる答え。同じ日なら誰が遊んでも同じ数になる。
+    /// rand の版や環境で変わらないように、SplitMix64 で日付を混ぜて決める
+    pub fn secret_for(date: NaiveDate, range: NumberRange) -> i64 {
+        let mut z = (date.num_days_from_ce() as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (range.min as i128 + (z as u128 % range.size()) as i128) as i64
+    }
+
+    pub fn today_record(&self) -> Option<&DailyRecord> {
+        self.records.iter().find(|record| record.date == self.today)
+    }
+
+    /// 今日のチャレンジを始める。もう遊んでいたら None。
+    /// やり直しで答えを引き直せないように、始めた時点で負けとして記録しておく
+    pub fn start(&mut self, cx: &mut Context<Self>) -> Option<i64> {
+        self.today = Local::now().date_naive();
+        if self.today_record().is_some() {
+            self.active = false;
+            cx.notify();
+            return None;
+        }
+        self.active = true;
+        self.message = String::new().into();
+        self.records.push(DailyRecord {
+            date: self.today,
+            won: false,
+            attempts: 0,
+            share_text: format!("Guessing Game Daily {} X", self.today),
+            finished: false,
+        });
+        self.save();
+        cx.notify();
+        Some(Self::secret_for(self.today, DAILY_DIFFICULTY.range()))
+    }
+
+    /// デイリーを途中でやめたら、始めたときの負けの記録で確定する
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        let today = self.today;
+        if self.active
+            && let Some(record) = self
+                .records
+                .iter_mut()
+                .find(|record| record.date == today && !record.finished)
+        {
+            record.finished = true;
+            self.save();
+        }
+        self.active = false;
+        cx.notify();
+    }
+
+    /// 決着がついたゲームを記録して、共有用の文字列を返す
+    pub fn finish(&mut self, game: &GameState) -> Option<String> {
+        if !self.active || game.status == GameStatus::Playing {
+            return None;
+        }
+        let today = self.today;
+        let share_text = Self::share_text(today, game);
+        let record = self
+            .records
+            .iter_mut()
+            .find(|record| record.date == today && !record.finished)?;
+        *record = DailyRecord {
+            date: today,
+            won: game.status == GameStatus::Won,
+            attempts: game.attempts,
+            share_text: share_text.clone(),
+            finished: true,
+        };
+        self.save();
+        Some(share_text)
+    }
+
+    /// 答えがわからないように、向きだけを並べる
+    pub fn share_text(date: NaiveDate, game: &GameState) -> String {
+        let score = match (game.status, game.max_attempts()) {
+            (GameStatus::Won, Some(max)) => format!("{}/{}", game.attempts, max),
+            (GameStatus::Won, None) => game.attempts.to_string(),
+            (_, Some(max)) => format!("X/{}", max),
+            (_, None) => "X".to_string(),
+        };
+        let arrows: String = game
+            .history
+            .iter()
+            .map(|record| match record.outcome {
+                GuessOutcome::Correct => "🎯",
+                GuessOutcome::TooHigh => "⬇️",
+                GuessOutcome::TooLow => "⬆️",
+            })
+            .collect();
+        format!("Guessing Game Daily {} {}\n{}", date, score, arrows)
+    }
+
+    /// (今の連続日数, 最高の連続日数)。今日がまだなら昨日までの連続を数える
+    pub fn streaks(&self) -> (u32, u32) {
+        let mut won_dates: Vec<NaiveDate> = self
+            .records
+            .iter()
+            .filter(|record| record.won)
+            .map(|record| record.date)
+            .collect();
+        won_dates.sort();
+        won_dates.dedup();
+
+        let mut best = 0;
+        let mut run = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for &date in &won_dates {
+            run = match previous {
+                Some(previous) if previous.succ_opt() == Some(date) => run + 1,
+                _ => 1,
+            };
+            best = best.max(run);
+            previous = Some(date);
+        }
+
+        let mut current = 0;
+        let mut day = if won_dates.contains(&self.today) {
+            Some(self.today)
+        } else {
+            self.today.pred_opt()
+        };
+        while let Some(date) = day
+            && won_dates.contains(&date)
+        {
+            current += 1;
+            day = date.pred_opt();
+        }
+        (current, best)
+    }
+
+    /// $XDG_DATA_HOME/guessing_game/daily.json (なければ ~/.local/share/guessing_game/daily.json)
+    pub fn data_path() -> Option<PathBuf> {
+        storage::xdg_path("XDG_DATA_HOME", ".local/share", "daily.json")
+    }
+
+    fn load() -> Result<Option<Vec<DailyRecord>>, String> {
+        let Some(path) = Self::data_path() else {
+            return Ok(None);
+        };
+        storage::load_json(&path)
+    }
+
+    fn save(&mut self) {
+        let Some(path) = Self::data_path() else {
+            return;
+        };
+        if let Err(err) = storage::save_json(&path, &self.records) {
+            self.message = format!("デイリーの記録の保存に失敗: {}", err).into();
+        }
+    }
+}
+
+impl Default for DailyState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_is_fixed_per_date_and_inside_the_range() {
+        let range = DAILY_DIFFICULTY.range();
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        // 値が変わると過去の日と答えがずれるので、固定しておく
+        assert_eq!(DailyState::secret_for(date, range), 28);
+        let later = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        assert_eq!(DailyState::secret_for(later, range), 47);
+        let mut day = date;
+        for _ in 0..365 {
+            assert!(range.contains(DailyState::secret_for(day, range)));
+            day = day.succ_opt().unwrap();
+        }
+        let wide = NumberRange::new(i64::MIN, i64::MAX).unwrap();
+        let _ = DailyState::secret_for(date, wide);
+    }
+}
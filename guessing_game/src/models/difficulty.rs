@@ -0,0 +1,102 @@
+use crate::models::number_format::group;
+use std::fmt;
+
+/// 答えが含まれる範囲(両端を含む)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct NumberRange {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl NumberRange {
+    pub fn new(min: i64, max: i64) -> Option<Self> {
+        if min < max {
+            Some(NumberRange { min, max })
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, num: i64) -> bool {
+        (self.min..=self.max).contains(&num)
+    }
+
+    /// 範囲に含まれる数の個数。i64 の端から端までは u64 に収まらないので u128 にする
+    pub fn size(&self) -> u128 {
+        (self.max as i128 - self.min as i128 + 1) as u128
+    }
+
+    /// 二分探索で必ず当てられる回数 ⌈log2(n)⌉。回数制限もここから決める
+    pub fn optimal_attempts(&self) -> u32 {
+        u128::BITS - (self.size() - 1).leading_zeros()
+    }
+
+    /// 真ん中の数(2つあれば小さいほう)。端どうしでもあふれない
+    pub fn midpoint(&self) -> i64 {
+        (self.min as i128 + (self.max as i128 - self.min as i128) / 2) as i64
+    }
+
+    /// num が範囲の中でどのあたりかを 0.0〜1.0 で返す
+    pub fn ratio_of(&self, num: i64) -> f64 {
+        (num as i128 - self.min as i128) as f64 / self.size() as f64
+    }
+
+    /// 範囲の中で ratio (0.0〜1.0) の位置にある数
+    pub fn at_ratio(&self, ratio: f64) -> i64 {
+        let span = (self.max as i128 - self.min as i128) as f64;
+        let offset = (span * ratio.clamp(0.0, 1.0)).round() as i128;
+        (self.min as i128 + offset).clamp(self.min as i128, self.max as i128) as i64
+    }
+
+    /// 入力欄に打てる文字数。マイナスと区切りのカンマも数える
+    pub fn max_chars(&self) -> usize {
+        group(self.min).len().max(group(self.max).len())
+    }
+}
+
+impl fmt::Display for NumberRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}〜{}", group(self.min), group(self.max))
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Expert,
+    Custom(NumberRange),
+}
+
+impl Difficulty {
+    pub const PRESETS: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    pub fn range(&self) -> NumberRange {
+        match self {
+            Difficulty::Easy => NumberRange { min: 1, max: 10 },
+            Difficulty::Normal => NumberRange { min: 1, max: 100 },
+            Difficulty::Hard => NumberRange { min: 1, max: 1000 },
+            Difficulty::Expert => NumberRange {
+                min: 1,
+                max: 1_000_000,
+            },
+            Difficulty::Custom(range) => *range,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+            Difficulty::Custom(_) => "Custom",
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::models::game_state::{GameState, GuessOutcome};
+use gpui::Context;
+use rodio::{OutputStream, Sink};
+use std::time::Duration;
+
+/// 予想したときに返す演出の種類
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Feedback {
+    Invalid,
+    TooLow,
+    TooHigh,
+    Correct,
+}
+
+impl Feedback {
+    /// 鳴らす音の高さ(Hz)と長さ(ミリ秒)の並び
+    fn tones(&self) -> &'static [(f32, u64)] {
+        match self {
+            Feedback::Invalid => &[(196.0, 90), (147.0, 140)],
+            Feedback::TooLow => &[(440.0, 70), (587.0, 90)],
+            Feedback::TooHigh => &[(587.0, 70), (440.0, 90)],
+            Feedback::Correct => &[(523.0, 90), (659.0, 90), (784.0, 90), (1047.0, 220)],
+        }
+    }
+}
+
+/// 予想への反応の演出と、その設定
+pub struct EffectsState {
+    pub muted: bool,
+    pub reduced_motion: bool,
+    pub feedback: Option<Feedback>,
+    /// 演出のたびに増やす。アニメーションの id にして最初から再生させる
+    pub seq: usize,
+    seen_submissions: u32,
+    /// 最初に鳴らすときに開いて、あとは同じ出力に足していく
+    audio: Option<(OutputStream, Sink)>,
+    /// 出力先が開けなかったら、予想のたびに開き直さない
+    audio_unavailable: bool,
+}
+
+impl EffectsState {
+    pub fn new() -> Self {
+        EffectsState {
+            muted: false,
+            reduced_motion: false,
+            feedback: None,
+            seq: 0,
+            seen_submissions: 0,
+            audio: None,
+            audio_unavailable: false,
+        }
+    }
+
+    /// 新しい予想があれば演出を決めて音を鳴らす。変わったら true
+    pub fn react(&mut self, game: &GameState) -> bool {
+        if game.submissions == self.seen_submissions {
+            // Reloadで始め直したら前の演出は消す
+            if game.history.is_empty() && game.last_error.is_none() && self.feedback.is_some() {
+                self.feedback = None;
+                return true;
+            }
+            return false;
+        }
+        self.seen_submissions = game.submissions;
+        let feedback = match (game.last_error, game.history.last()) {
+            (Some(_), _) | (None, None) => Feedback::Invalid,
+            (None, Some(record)) => match record.outcome {
+                GuessOutcome::TooLow => Feedback::TooLow,
+                GuessOutcome::TooHigh => Feedback::TooHigh,
+                GuessOutcome::Correct => Feedback::Correct,
+            },
+        };
+        self.feedback = Some(feedback);
+        self.seq += 1;
+        if !self.muted {
+            self.play_sound(feedback);
+        }
+        true
+    }
+
+    pub fn toggle_muted(&mut self, cx: &mut Context<Self>) {
+        self.muted = !self.muted;
+        cx.notify();
+    }
+
+    pub fn toggle_reduced_motion(&mut self, cx: &mut Context<Self>) {
+        self.reduced_motion = !self.reduced_motion;
+        cx.notify();
+    }
+
+    fn sink(&mut self) -> Option<&Sink> {
+        if self.audio.is_none() && !self.audio_unavailable {
+            let opened = OutputStream::try_default()
+                .ok()
+                .and_then(|(stream, handle)| Some((stream, Sink::try_new(&handle).ok()?)));
+            self.audio_unavailable = opened.is_none();
+            self.audio = opened;
+        }
+        self.audio.as_ref().map(|(_, sink)| sink)
+    }
+
+    /// 短い音を共有の Sink に足す。再生はオーディオのスレッドがするので待たない。
+    /// 出力先がなければ何もしない
+    fn play_sound(&mut self, feedback: Feedback) {
+        use rodio::source::{SineWave, Source};
+
+        let Some(sink) = self.sink() else {
+            return;
+        };
+        for &(frequency, millis) in feedback.tones() {
+            sink.append(
+                SineWave::new(frequency)
+                    .take_duration(Duration::from_millis(millis))
+                    .amplify(0.12),
+            );
+        }
+    }
+}
+
+impl Default for EffectsState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,62 +1,501 @@
+use crate::models::difficulty::{Difficulty, NumberRange};
+use crate::models::number_format::{group, parse_grouped};
+use crate::models::oracle::{Honest, Oracle};
+use crate::models::secret::{FixedSecret, Secret, SecretKind};
 use gpui::Context;
 use gpui::SharedString;
-use rand::Rng;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// 最短回数に足す余裕の回数
+pub const EXTRA_ATTEMPTS: u32 = 2;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GameStatus {
+    Playing,
+    Won,
+    Lost,
+}
+
+/// 予想した数を答えと比べた結果
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GuessOutcome {
+    TooLow,
+    TooHigh,
+    Correct,
+}
+
+impl GuessOutcome {
+    /// 答えが secret のときの正直な答え
+    pub fn of(guess: i64, secret: i64) -> Self {
+        match guess.cmp(&secret) {
+            std::cmp::Ordering::Less => GuessOutcome::TooLow,
+            std::cmp::Ordering::Greater => GuessOutcome::TooHigh,
+            std::cmp::Ordering::Equal => GuessOutcome::Correct,
+        }
+    }
+}
+
+/// 予想として受け付けなかった理由
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GuessError {
+    Empty,
+    NotANumber,
+    OutOfRange(NumberRange),
+    AlreadyGuessed(i64),
+    GameOver,
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuessError::Empty => write!(f, "数を入力してね"),
+            GuessError::NotANumber => write!(f, "数で入力してね (例: -1,234)"),
+            GuessError::OutOfRange(range) => write!(f, "{} の数を入力してね", range),
+            GuessError::AlreadyGuessed(num) => write!(f, "{} はもう予想したよ", group(*num)),
+            GuessError::GameOver => write!(f, "決着がついたよ。Reloadで次のゲームへ"),
+        }
+    }
+}
+
+/// 1回分の予想とその結果
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct GuessRecord {
+    pub value: i64,
+    pub outcome: GuessOutcome,
+}
 
 pub struct GameState {
-    secret_number: u32,
+    secret: Box<dyn Secret>,
+    pub secret_kind: SecretKind,
+    pub difficulty: Difficulty,
+    pub limit_attempts: bool,
+    pub status: GameStatus,
+    pub attempts: u32,
+    pub score: u32,
+    pub started_at: Instant,
+    pub finished_at: Option<Instant>,
+    /// 1ラウンドの制限時間。過ぎたら負け
+    pub time_limit: Option<Duration>,
+    pub history: Vec<GuessRecord>,
     pub current_input: SharedString,
     pub message: SharedString,
+    pub last_error: Option<GuessError>,
+    /// 予想しようとした回数。受け付けなかったものも数え、Reloadでも戻さない
+    pub submissions: u32,
+    /// 予想に答える相手。嘘をつくこともある
+    oracle: Box<dyn Oracle>,
 }
 
 impl GameState {
-    pub fn new() -> Self {
+    pub fn new(difficulty: Difficulty) -> Self {
+        let range = difficulty.range();
         GameState {
-            secret_number: rand::rng().random_range(1..=99),
+            secret: SecretKind::Random.build(range),
+            secret_kind: SecretKind::Random,
+            difficulty,
+            limit_attempts: true,
+            status: GameStatus::Playing,
+            attempts: 0,
+            score: 0,
+            started_at: Instant::now(),
+            finished_at: None,
+            time_limit: None,
+            history: Vec::new(),
             current_input: String::new().into(),
             message: String::new().into(),
+            last_error: None,
+            submissions: 0,
+            oracle: Box::new(Honest),
         }
     }
     pub fn reload(&mut self) {
-        self.secret_number = rand::rng().random_range(1..=99);
+        self.reset(self.secret_kind.build(self.range()));
+    }
+    /// 答えを外から決めて始め直す(対戦で相手が決めた数など)。範囲外ならfalse
+    pub fn reload_with_secret(&mut self, secret: i64) -> bool {
+        if !self.range().contains(secret) {
+            return false;
+        }
+        self.reset(Box::new(FixedSecret(secret)));
+        true
+    }
+    fn reset(&mut self, secret: Box<dyn Secret>) {
+        self.secret = secret;
+        self.status = GameStatus::Playing;
+        self.attempts = 0;
+        self.score = 0;
+        self.started_at = Instant::now();
+        self.finished_at = None;
+        self.history.clear();
         self.current_input = String::new().into();
         self.message = String::new().into();
+        self.last_error = None;
+        self.oracle.reset();
     }
-    pub fn sub_guess(&mut self, cx: &mut Context<Self>) {
-        if let Ok(num) = self.current_input.parse::<u32>() {
-            let result = self.guess(num);
-            println!("Guess: {}", num);
-
-            if result.is_true {
-                self.message = "正解！".into();
-            } else {
-                self.message = if result.is_bigger {
-                    "もっと小さい"
-                } else {
-                    "もっと大きい"
-                }
-                .into();
+    /// 答えの決め方を変えて始め直す
+    pub fn set_secret_kind(&mut self, secret_kind: SecretKind) {
+        self.secret_kind = secret_kind;
+        self.reload();
+    }
+    /// 答える相手を差し替えて始め直す
+    pub fn set_oracle(&mut self, oracle: Box<dyn Oracle>) {
+        self.oracle = oracle;
+        self.reload();
+    }
+    /// 1ゲームでつかれる嘘の上限
+    pub fn max_lies(&self) -> u32 {
+        self.oracle.max_lies()
+    }
+    pub fn set_difficulty(&mut self, difficulty: Difficulty, limit_attempts: bool) {
+        self.difficulty = difficulty;
+        self.limit_attempts = limit_attempts;
+        self.reload();
+    }
+    pub fn range(&self) -> NumberRange {
+        self.difficulty.range()
+    }
+    /// これまでの結果から、まだ答えの可能性がある範囲
+    pub fn possible_range(&self) -> NumberRange {
+        self.possible_before(self.history.len())
+    }
+    /// index 回目の予想をする前に、答えの可能性があった範囲。
+    /// 嘘の上限を超えずに説明できる候補の、いちばん小さい数から大きい数まで
+    pub fn possible_before(&self, index: usize) -> NumberRange {
+        let segments = self.candidate_segments(index);
+        let max_lies = self.max_lies();
+        let mut candidates = segments
+            .iter()
+            .filter(|(_, lies)| *lies <= max_lies)
+            .map(|(segment, _)| *segment);
+        match (candidates.next(), candidates.next_back()) {
+            (Some(first), last) => NumberRange {
+                min: first.min,
+                max: last.unwrap_or(first).max,
+            },
+            (None, _) => self.range(),
+        }
+    }
+    /// 答えがどこにあっても、まだつける嘘の回数の上限
+    pub fn lies_left(&self) -> u32 {
+        let told = self
+            .candidate_segments(self.history.len())
+            .iter()
+            .map(|(_, lies)| *lies)
+            .min()
+            .unwrap_or(0);
+        self.max_lies().saturating_sub(told)
+    }
+    /// 予想した数で範囲を区切り、区間ごとに答えと食い違った回数を数える。
+    /// 予想した数そのものは(正解でない限り)候補から外れる
+    fn candidate_segments(&self, index: usize) -> Vec<(NumberRange, u32)> {
+        let history = &self.history[..index.min(self.history.len())];
+        if let Some(record) = history
+            .iter()
+            .find(|record| record.outcome == GuessOutcome::Correct)
+        {
+            let exact = NumberRange {
+                min: record.value,
+                max: record.value,
+            };
+            return vec![(exact, 0)];
+        }
+        let range = self.range();
+        let mut values: Vec<i64> = history.iter().map(|record| record.value).collect();
+        values.sort_unstable();
+        values.dedup();
+
+        let mut segments = Vec::new();
+        let mut start = Some(range.min);
+        for value in values {
+            if let Some(min) = start.filter(|min| *min < value) {
+                segments.push(NumberRange {
+                    min,
+                    max: value - 1,
+                });
             }
+            start = value.checked_add(1).filter(|next| *next <= range.max);
+        }
+        if let Some(min) = start {
+            segments.push(NumberRange {
+                min,
+                max: range.max,
+            });
+        }
+        segments
+            .into_iter()
+            .map(|segment| {
+                let lies = history
+                    .iter()
+                    .filter(|record| match record.outcome {
+                        GuessOutcome::TooLow => segment.min < record.value,
+                        GuessOutcome::TooHigh => segment.min > record.value,
+                        GuessOutcome::Correct => false,
+                    })
+                    .count() as u32;
+                (segment, lies)
+            })
+            .collect()
+    }
+    /// 記録した答えが嘘だったか。明かす答えと比べるので決着後に使う
+    pub fn is_lie(&self, record: &GuessRecord) -> bool {
+        record.outcome != GuessOutcome::of(record.value, self.secret.reveal())
+    }
+    pub fn lies_told(&self) -> usize {
+        self.history
+            .iter()
+            .filter(|record| self.is_lie(record))
+            .count()
+    }
+    /// 回数制限があるときの上限。⌈log2(n)⌉ + EXTRA_ATTEMPTS。
+    /// 嘘をつかれる場合は、嘘1回ごとに最短回数の半分ほど余分に認める
+    pub fn max_attempts(&self) -> Option<u32> {
+        let optimal = self.range().optimal_attempts();
+        self.limit_attempts
+            .then(|| optimal + EXTRA_ATTEMPTS + self.max_lies() * (optimal / 2 + 2))
+    }
+    pub fn elapsed(&self) -> Duration {
+        self.finished_at.unwrap_or_else(Instant::now) - self.started_at
+    }
+    /// 制限時間までの残り
+    pub fn remaining(&self) -> Option<Duration> {
+        self.time_limit
+            .map(|limit| limit.saturating_sub(self.elapsed()))
+    }
+    /// 制限時間を過ぎていたら負けにする。負けにしたら true
+    pub fn check_timeout(&mut self) -> bool {
+        if self.status != GameStatus::Playing || !self.remaining().is_some_and(|r| r.is_zero()) {
+            return false;
+        }
+        self.status = GameStatus::Lost;
+        self.finished_at = self.time_limit.map(|limit| self.started_at + limit);
+        self.message = format!(
+            "時間切れ… 答えは {}{}",
+            group(self.secret.reveal()),
+            self.lies_summary()
+        )
+        .into();
+        true
+    }
+    /// 残り回数が多いほど、早く当てるほど高い。
+    /// 制限なしのときは最短回数+余裕を基準にする
+    pub fn calc_score(&self) -> u32 {
+        let par = self.range().optimal_attempts() + EXTRA_ATTEMPTS;
+        let remaining = par.saturating_sub(self.attempts) + 1;
+        let time_bonus = 300u32.saturating_sub(self.elapsed().as_secs() as u32);
+        remaining * 100 + time_bonus
+    }
+    /// 入力の末尾に数字を足す。範囲に負の数があれば先頭にマイナスも打てる
+    pub fn push_digit(&mut self, key: &str) {
+        let range = self.range();
+        let accepted = ("0"..="9").contains(&key)
+            || (key == "-" && self.current_input.is_empty() && range.min < 0);
+        if accepted && self.current_input.len() < range.max_chars() {
+            self.current_input = format!("{}{}", self.current_input, key).into();
+        }
+    }
+    pub fn pop_digit(&mut self) {
+        let mut chars = self.current_input.chars();
+        chars.next_back();
+        self.current_input = chars.as_str().to_string().into();
+    }
+    pub fn clear_input(&mut self) {
+        self.current_input = String::new().into();
+    }
+    /// スライダーで選んだ数を区切って入力にする
+    pub fn set_input(&mut self, num: i64) {
+        let range = self.range();
+        self.current_input = group(num.clamp(range.min, range.max)).into();
+    }
+    /// 入力中の数を1つ増減する。空なら残っている範囲の真ん中から始める
+    pub fn step_input(&mut self, delta: i64) {
+        let next = match parse_grouped(&self.current_input) {
+            Some(num) => num.saturating_add(delta),
+            None => self.possible_range().midpoint(),
+        };
+        self.set_input(next);
+    }
+
+    pub fn sub_guess(&mut self, cx: &mut Context<Self>) {
+        let input = self.current_input.clone();
+        if self.submit(&input).is_ok() {
+            println!("Guess: {}", input);
         }
         cx.notify();
     }
 
-    pub fn guess(&mut self, guess: u32) -> Answer {
-        let is_true = guess == self.secret_number;
-        let is_bigger = guess > self.secret_number;
-        Answer { is_true, is_bigger }
+    /// 入力を検証してから予想する。結果は戻り値と last_error の両方に残す
+    pub fn submit(&mut self, input: &str) -> Result<GuessOutcome, GuessError> {
+        let result = self.try_submit(input);
+        self.last_error = result.err();
+        self.submissions = self.submissions.wrapping_add(1);
+        result
     }
-}
 
-pub struct Answer {
-    is_true: bool,
-    is_bigger: bool,
+    fn try_submit(&mut self, input: &str) -> Result<GuessOutcome, GuessError> {
+        // 決着がついたら次のReloadまで受け付けない
+        if self.status != GameStatus::Playing {
+            return Err(GuessError::GameOver);
+        }
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(GuessError::Empty);
+        }
+        let num = parse_grouped(input).ok_or(GuessError::NotANumber)?;
+        let range = self.range();
+        if !range.contains(num) {
+            return Err(GuessError::OutOfRange(range));
+        }
+        // 嘘をつかれる場合は同じ数を聞き直してもよい
+        if self.max_lies() == 0 && self.history.iter().any(|record| record.value == num) {
+            return Err(GuessError::AlreadyGuessed(num));
+        }
+
+        let outcome = self.guess(num);
+        self.attempts += 1;
+        self.history.push(GuessRecord {
+            value: num,
+            outcome,
+        });
+
+        if outcome == GuessOutcome::Correct {
+            self.status = GameStatus::Won;
+            self.finished_at = Some(Instant::now());
+            self.score = self.calc_score();
+            self.message = format!(
+                "正解！ 答えは {} ({}回, スコア {}){}",
+                group(self.secret.reveal()),
+                self.attempts,
+                self.score,
+                self.lies_summary()
+            )
+            .into();
+        } else if self.max_attempts().is_some_and(|max| self.attempts >= max) {
+            self.status = GameStatus::Lost;
+            self.finished_at = Some(Instant::now());
+            self.message = format!(
+                "残念… 答えは {}{}",
+                group(self.secret.reveal()),
+                self.lies_summary()
+            )
+            .into();
+        } else {
+            self.message = match outcome {
+                GuessOutcome::TooHigh => "もっと小さい",
+                _ => "もっと大きい",
+            }
+            .into();
+        }
+        Ok(outcome)
+    }
+
+    /// 決着の言葉に添える、嘘をついた回数
+    fn lies_summary(&self) -> String {
+        if self.max_lies() == 0 {
+            return String::new();
+        }
+        format!(" 嘘は {} 回", self.lies_told())
+    }
+
+    /// 予想への答え。secret の正直な答えをもとに oracle が決める
+    pub fn guess(&mut self, guess: i64) -> GuessOutcome {
+        let truth = self.secret.compare(guess);
+        let possible = self.possible_range();
+        self.oracle.answer(guess, truth, possible)
+    }
 }
 
-impl Answer {
-    pub fn new() -> Self {
-        Answer {
-            is_true: false,
-            is_bigger: false,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1〜100、回数制限ありで答えを決めたゲーム
+    fn game_with_secret(secret: i64) -> GameState {
+        let mut game = GameState::new(Difficulty::Normal);
+        assert!(game.reload_with_secret(secret));
+        game
+    }
+
+    #[test]
+    fn rejected_input_is_reported_and_not_counted() {
+        let mut game = game_with_secret(50);
+        assert_eq!(game.submit("   "), Err(GuessError::Empty));
+        assert_eq!(game.submit("12abc"), Err(GuessError::NotANumber));
+        assert_eq!(game.submit("1.5"), Err(GuessError::NotANumber));
+        assert_eq!(
+            game.submit("101"),
+            Err(GuessError::OutOfRange(game.range()))
+        );
+        assert_eq!(game.submit("0"), Err(GuessError::OutOfRange(game.range())));
+        assert_eq!(game.submit("20"), Ok(GuessOutcome::TooLow));
+        assert_eq!(game.submit("20"), Err(GuessError::AlreadyGuessed(20)));
+        assert_eq!(game.last_error, Some(GuessError::AlreadyGuessed(20)));
+        assert_eq!(game.attempts, 1);
+        assert_eq!(game.submissions, 7);
+        assert_eq!(game.status, GameStatus::Playing);
+    }
+
+    #[test]
+    fn correct_guess_wins_and_closes_the_game() {
+        let mut game = game_with_secret(42);
+        assert_eq!(game.submit("80"), Ok(GuessOutcome::TooHigh));
+        assert_eq!(game.submit("42"), Ok(GuessOutcome::Correct));
+        assert_eq!(game.status, GameStatus::Won);
+        assert_eq!(game.attempts, 2);
+        assert!(game.finished_at.is_some());
+        assert_eq!(game.last_error, None);
+        assert_eq!(game.submit("1"), Err(GuessError::GameOver));
+        assert_eq!(game.attempts, 2);
+    }
+
+    #[test]
+    fn game_is_lost_on_the_last_allowed_attempt() {
+        let mut game = game_with_secret(100);
+        let max = game.max_attempts().unwrap();
+        assert_eq!(max, 7 + EXTRA_ATTEMPTS);
+        for guess in 1..max as i64 {
+            assert_eq!(game.submit(&guess.to_string()), Ok(GuessOutcome::TooLow));
+            assert_eq!(game.status, GameStatus::Playing);
+        }
+        assert_eq!(game.submit(&max.to_string()), Ok(GuessOutcome::TooLow));
+        assert_eq!(game.status, GameStatus::Lost);
+        assert!(game.finished_at.is_some());
+        assert_eq!(game.submit("100"), Err(GuessError::GameOver));
+    }
+
+    #[test]
+    fn win_on_the_last_attempt_is_still_a_win() {
+        let mut game = game_with_secret(100);
+        let max = game.max_attempts().unwrap() as i64;
+        for guess in 1..max {
+            game.submit(&guess.to_string()).unwrap();
         }
+        assert_eq!(game.submit("100"), Ok(GuessOutcome::Correct));
+        assert_eq!(game.status, GameStatus::Won);
+    }
+
+    #[test]
+    fn without_a_limit_the_game_is_never_lost() {
+        let mut game = game_with_secret(100);
+        game.set_difficulty(Difficulty::Normal, false);
+        assert!(game.reload_with_secret(100));
+        assert_eq!(game.max_attempts(), None);
+        for guess in 1..100 {
+            game.submit(&guess.to_string()).unwrap();
+        }
+        assert_eq!(game.status, GameStatus::Playing);
+        assert_eq!(game.submit("100"), Ok(GuessOutcome::Correct));
+        assert_eq!(game.attempts, 100);
+    }
+
+    #[test]
+    fn reload_clears_the_previous_game() {
+        let mut game = game_with_secret(42);
+        game.submit("42").unwrap();
+        assert!(game.reload_with_secret(7));
+        assert_eq!(game.status, GameStatus::Playing);
+        assert_eq!(game.attempts, 0);
+        assert!(game.history.is_empty());
+        assert!(!game.reload_with_secret(1000));
     }
 }
@@ -0,0 +1,130 @@
+use crate::models::difficulty::NumberRange;
+use crate::models::number_format::parse_grouped;
+use gpui::Context;
+use gpui::SharedString;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum HotSeatPhase {
+    /// 出題する人が答えを入力している
+    SetSecret,
+    /// 当てる人に端末を渡すのを待っている
+    Handover,
+    Guessing,
+    MatchOver,
+}
+
+/// ひとつの端末を交代で使うふたり対戦
+pub struct HotSeatState {
+    pub total_rounds: u32,
+    pub round: u32,
+    pub setter: usize,
+    pub phase: HotSeatPhase,
+    pub secret_input: SharedString,
+    pub round_attempts: [Option<u32>; 2],
+    pub total_attempts: [u32; 2],
+    pub points: [u32; 2],
+    pub message: SharedString,
+}
+
+impl HotSeatState {
+    pub fn new() -> Self {
+        HotSeatState {
+            total_rounds: 1,
+            round: 1,
+            setter: 0,
+            phase: HotSeatPhase::SetSecret,
+            secret_input: String::new().into(),
+            round_attempts: [None; 2],
+            total_attempts: [0; 2],
+            points: [0; 2],
+            message: String::new().into(),
+        }
+    }
+
+    pub fn player_name(player: usize) -> String {
+        format!("Player {}", player + 1)
+    }
+
+    pub fn guesser(&self) -> usize {
+        1 - self.setter
+    }
+
+    pub fn start(&mut self, total_rounds: u32, cx: &mut Context<Self>) {
+        *self = HotSeatState {
+            total_rounds,
+            ..HotSeatState::new()
+        };
+        cx.notify();
+    }
+
+    pub fn push_digit(&mut self, key: &str, range: NumberRange, cx: &mut Context<Self>) {
+        let accepted = key != "-" || (self.secret_input.is_empty() && range.min < 0);
+        if accepted && self.secret_input.len() < range.max_chars() {
+            self.secret_input = format!("{}{}", self.secret_input, key).into();
+        }
+        cx.notify();
+    }
+
+    pub fn pop_digit(&mut self, cx: &mut Context<Self>) {
+        let mut chars = self.secret_input.chars();
+        chars.next_back();
+        self.secret_input = chars.as_str().to_string().into();
+        cx.notify();
+    }
+
+    /// 入力した答えを取り出す。入力欄は空にする
+    pub fn take_secret(&mut self) -> Option<i64> {
+        let secret = parse_grouped(&self.secret_input);
+        self.secret_input = String::new().into();
+        secret
+    }
+
+    pub fn set_phase(&mut self, phase: HotSeatPhase, cx: &mut Context<Self>) {
+        self.phase = phase;
+        self.message = String::new().into();
+        cx.notify();
+    }
+
+    /// 当てる人の番が終わったら記録して役を交代する。
+    /// 当てられなかったときは上限+1回として数える
+    pub fn finish_turn(&mut self, attempts: u32, won: bool, cx: &mut Context<Self>) {
+        let guesser = self.guesser();
+        let attempts = if won { attempts } else { attempts + 1 };
+        self.round_attempts[guesser] = Some(attempts);
+        self.total_attempts[guesser] += attempts;
+
+        // ふたりとも当て終わったら少ない回数の方に1点
+        if let [Some(first), Some(second)] = self.round_attempts {
+            if first < second {
+                self.points[0] += 1;
+            } else if second < first {
+                self.points[1] += 1;
+            }
+            self.round_attempts = [None; 2];
+            self.round += 1;
+        }
+
+        self.setter = guesser;
+        self.phase = if self.round > self.total_rounds {
+            HotSeatPhase::MatchOver
+        } else {
+            HotSeatPhase::SetSecret
+        };
+        self.message = String::new().into();
+        cx.notify();
+    }
+
+    pub fn winner_message(&self) -> String {
+        match self.points[0].cmp(&self.points[1]) {
+            std::cmp::Ordering::Greater => format!("{} の勝ち！", Self::player_name(0)),
+            std::cmp::Ordering::Less => format!("{} の勝ち！", Self::player_name(1)),
+            std::cmp::Ordering::Equal => "引き分け".to_string(),
+        }
+    }
+}
+
+impl Default for HotSeatState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,83 @@
+/// 3桁ごとにカンマで区切る。-1234567 なら "-1,234,567"
+pub fn group(num: i64) -> String {
+    let digits = num.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    if num < 0 {
+        grouped.push('-');
+    }
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+/// 入力欄に貼り付けた文字を揃える。全角の数字とマイナスは半角にし、
+/// 数字・マイナス・区切り(カンマ、アンダースコア)以外は捨てる
+pub fn normalize_number(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            '0'..='9' | '-' | ',' | '_' => Some(c),
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32),
+            '－' | '−' => Some('-'),
+            '，' => Some(','),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 先頭のマイナスと、カンマ・アンダースコアの区切りを許して読む。
+/// それ以外の文字が混ざっていたら数として読まない
+pub fn parse_grouped(input: &str) -> Option<i64> {
+    let mut cleaned = String::with_capacity(input.len());
+    for (i, c) in input.trim().chars().enumerate() {
+        match c {
+            '0'..='9' => cleaned.push(c),
+            '０'..='９' => cleaned.push(char::from_u32(c as u32 - '０' as u32 + '0' as u32)?),
+            '-' | '－' | '−' if i == 0 => cleaned.push('-'),
+            ',' | '_' | '，' => {}
+            _ => return None,
+        }
+    }
+    let digits = cleaned.strip_prefix('-').unwrap_or(&cleaned);
+    if digits.is_empty() {
+        return None;
+    }
+    cleaned.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_inserts_commas() {
+        assert_eq!(group(0), "0");
+        assert_eq!(group(999), "999");
+        assert_eq!(group(1000), "1,000");
+        assert_eq!(group(-1234567), "-1,234,567");
+        assert_eq!(group(i64::MIN), "-9,223,372,036,854,775,808");
+    }
+
+    #[test]
+    fn parse_grouped_accepts_separators_and_sign() {
+        assert_eq!(parse_grouped("1,234"), Some(1234));
+        assert_eq!(parse_grouped("1_000_000"), Some(1_000_000));
+        assert_eq!(parse_grouped(" -42 "), Some(-42));
+        assert_eq!(parse_grouped("－１２，３４５"), Some(-12345));
+        assert_eq!(parse_grouped(&group(i64::MIN)), Some(i64::MIN));
+        assert_eq!(parse_grouped(&group(i64::MAX)), Some(i64::MAX));
+    }
+
+    #[test]
+    fn parse_grouped_rejects_other_characters() {
+        for input in [
+            "1.5", "1e3", "12abc", "x-5", "5-", "--5", "1 000", "-", ",", "",
+        ] {
+            assert_eq!(parse_grouped(input), None, "{:?}", input);
+        }
+        assert_eq!(parse_grouped("9223372036854775808"), None);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::models::difficulty::NumberRange;
+use crate::models::game_state::GuessOutcome;
+use rand::Rng;
+
+/// 予想に対してプレイヤーへ返す答えを決める
+pub trait Oracle {
+    /// 正直な答え truth をもとに返す答えを決める。
+    /// possible は予想する前の、まだ答えの可能性がある範囲
+    fn answer(&mut self, guess: i64, truth: GuessOutcome, possible: NumberRange) -> GuessOutcome;
+    /// 1ゲームでつける嘘の上限
+    fn max_lies(&self) -> u32;
+    /// 新しいゲームのために嘘の回数を戻す
+    fn reset(&mut self);
+}
+
+/// いつも正直に答える
+pub struct Honest;
+
+impl Oracle for Honest {
+    fn answer(&mut self, _guess: i64, truth: GuessOutcome, _possible: NumberRange) -> GuessOutcome {
+        truth
+    }
+
+    fn max_lies(&self) -> u32 {
+        0
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// 大きい・小さいを逆にした答え。正解だけは嘘をつかない
+fn flip(truth: GuessOutcome) -> GuessOutcome {
+    match truth {
+        GuessOutcome::TooLow => GuessOutcome::TooHigh,
+        GuessOutcome::TooHigh => GuessOutcome::TooLow,
+        GuessOutcome::Correct => GuessOutcome::Correct,
+    }
+}
+
+/// 上限まで、ときどき気まぐれに嘘をつく
+pub struct RandomLiar {
+    pub max_lies: u32,
+    lies_told: u32,
+}
+
+impl RandomLiar {
+    /// 1回の答えで嘘をつく確率
+    const LIE_CHANCE: f64 = 1.0 / 3.0;
+
+    pub fn new(max_lies: u32) -> Self {
+        RandomLiar {
+            max_lies,
+            lies_told: 0,
+        }
+    }
+}
+
+impl Oracle for RandomLiar {
+    fn answer(&mut self, _guess: i64, truth: GuessOutcome, _possible: NumberRange) -> GuessOutcome {
+        if truth == GuessOutcome::Correct
+            || self.lies_told >= self.max_lies
+            || !rand::rng().random_bool(Self::LIE_CHANCE)
+        {
+            return truth;
+        }
+        self.lies_told += 1;
+        flip(truth)
+    }
+
+    fn max_lies(&self) -> u32 {
+        self.max_lies
+    }
+
+    fn reset(&mut self) {
+        self.lies_told = 0;
+    }
+}
+
+/// 本当の答えが狭いほうにあるときだけ、広いほうを指して嘘をつく
+pub struct AdversarialLiar {
+    pub max_lies: u32,
+    lies_told: u32,
+}
+
+impl AdversarialLiar {
+    pub fn new(max_lies: u32) -> Self {
+        AdversarialLiar {
+            max_lies,
+            lies_told: 0,
+        }
+    }
+}
+
+impl Oracle for AdversarialLiar {
+    fn answer(&mut self, guess: i64, truth: GuessOutcome, possible: NumberRange) -> GuessOutcome {
+        if self.lies_told >= self.max_lies || !possible.contains(guess) {
+            return truth;
+        }
+        let (below, above) = (
+            guess as i128 - possible.min as i128,
+            possible.max as i128 - guess as i128,
+        );
+        let lie = match truth {
+            GuessOutcome::TooLow => above < below,
+            GuessOutcome::TooHigh => below < above,
+            GuessOutcome::Correct => false,
+        };
+        if !lie {
+            return truth;
+        }
+        self.lies_told += 1;
+        flip(truth)
+    }
+
+    fn max_lies(&self) -> u32 {
+        self.max_lies
+    }
+
+    fn reset(&mut self) {
+        self.lies_told = 0;
+    }
+}
+
+/// 設定画面で選ぶ嘘のつき方
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum LiarKind {
+    Random,
+    Adversarial,
+}
+
+impl LiarKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LiarKind::Random => "気まぐれ",
+            LiarKind::Adversarial => "意地悪",
+        }
+    }
+
+    /// 嘘の上限が0なら正直な相手にする
+    pub fn build(&self, max_lies: u32) -> Box<dyn Oracle> {
+        match (max_lies, self) {
+            (0, _) => Box::new(Honest),
+            (_, LiarKind::Random) => Box::new(RandomLiar::new(max_lies)),
+            (_, LiarKind::Adversarial) => Box::new(AdversarialLiar::new(max_lies)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::difficulty::Difficulty;
+    use crate::models::game_state::{GameState, GameStatus};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn range() -> NumberRange {
+        NumberRange::new(1, 100).unwrap()
+    }
+
+    #[test]
+    fn liars_never_lie_about_a_correct_guess_or_past_their_limit() {
+        let oracles: [Box<dyn Oracle>; 3] = [
+            Box::new(Honest),
+            Box::new(RandomLiar::new(2)),
+            Box::new(AdversarialLiar::new(2)),
+        ];
+        for mut oracle in oracles {
+            for _ in 0..2 {
+                let mut lies = 0;
+                for guess in 1..=100 {
+                    let truth = GuessOutcome::of(guess, 1);
+                    let answer = oracle.answer(guess, truth, range());
+                    if truth == GuessOutcome::Correct {
+                        assert_eq!(answer, truth);
+                    }
+                    if answer != truth {
+                        lies += 1;
+                    }
+                }
+                assert!(lies <= oracle.max_lies());
+                // reset で次のゲームはまた嘘をつける
+                oracle.reset();
+            }
+        }
+    }
+
+    #[test]
+    fn adversarial_liar_only_points_to_the_larger_side() {
+        let mut oracle = AdversarialLiar::new(3);
+        // 答えは広いほう(上)にあるので正直に答える
+        assert_eq!(
+            oracle.answer(10, GuessOutcome::TooLow, range()),
+            GuessOutcome::TooLow
+        );
+        // 答えは狭いほう(下)にあるので、広いほうを指す
+        assert_eq!(
+            oracle.answer(10, GuessOutcome::TooHigh, range()),
+            GuessOutcome::TooLow
+        );
+        assert_eq!(
+            oracle.answer(90, GuessOutcome::TooLow, range()),
+            GuessOutcome::TooHigh
+        );
+    }
+
+    /// 嘘をつかれても、本当の答えはいつも残っている範囲に入っていて、
+    /// 嘘の回数は上限を超えない
+    #[test]
+    fn games_with_liars_stay_consistent_with_the_secret() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for kind in [LiarKind::Random, LiarKind::Adversarial] {
+            for max_lies in 1..=3 {
+                for _ in 0..50 {
+                    let secret = rng.random_range(1..=100);
+                    let mut game = GameState::new(Difficulty::Normal);
+                    game.set_difficulty(Difficulty::Normal, false);
+                    game.set_oracle(kind.build(max_lies));
+                    assert!(game.reload_with_secret(secret));
+                    for _ in 0..500 {
+                        let possible = game.possible_range();
+                        assert!(possible.contains(secret), "{:?} {}", possible, secret);
+                        if game.submit(&possible.midpoint().to_string())
+                            == Ok(GuessOutcome::Correct)
+                        {
+                            break;
+                        }
+                    }
+                    assert_eq!(game.status, GameStatus::Won);
+                    assert!(game.lies_told() <= max_lies as usize);
+                }
+            }
+        }
+    }
+}
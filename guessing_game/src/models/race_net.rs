@@ -0,0 +1,585 @@
+use crate::models::difficulty::NumberRange;
+use rand::Rng;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// 予想に対するホストの判定
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RaceHint {
+    TooBig,
+    TooSmall,
+    Correct,
+    /// ラウンド外や範囲外で受け付けなかった
+    Rejected,
+}
+
+impl RaceHint {
+    fn code(&self) -> &'static str {
+        match self {
+            RaceHint::TooBig => "BIG",
+            RaceHint::TooSmall => "SMALL",
+            RaceHint::Correct => "CORRECT",
+            RaceHint::Rejected => "REJECTED",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "BIG" => Some(RaceHint::TooBig),
+            "SMALL" => Some(RaceHint::TooSmall),
+            "CORRECT" => Some(RaceHint::Correct),
+            "REJECTED" => Some(RaceHint::Rejected),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RacePlayer {
+    pub id: usize,
+    pub name: String,
+    pub attempts: u32,
+    pub wins: u32,
+}
+
+/// ホストから各プレイヤーへ送る内容。1行1メッセージ
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ServerMessage {
+    Welcome { id: usize },
+    Round { round: u32, range: NumberRange },
+    Feedback { value: i64, hint: RaceHint },
+    Scores(Vec<RacePlayer>),
+    Winner { id: usize, name: String },
+}
+
+impl ServerMessage {
+    fn to_line(&self) -> String {
+        match self {
+            ServerMessage::Welcome { id } => format!("WELCOME {}", id),
+            ServerMessage::Round { round, range } => {
+                format!("ROUND {} {} {}", round, range.min, range.max)
+            }
+            ServerMessage::Feedback { value, hint } => {
+                format!("FEEDBACK {} {}", value, hint.code())
+            }
+            ServerMessage::Scores(players) => {
+                let players: Vec<String> = players
+                    .iter()
+                    .map(|p| format!("{},{},{},{}", p.id, p.attempts, p.wins, p.name))
+                    .collect();
+                format!("SCORES {}", players.join("|"))
+            }
+            ServerMessage::Winner { id, name } => format!("WINNER {} {}", id, name),
+        }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let mut args = rest.split(' ');
+        match command {
+            "WELCOME" => Some(ServerMessage::Welcome {
+                id: args.next()?.parse().ok()?,
+            }),
+            "ROUND" => Some(ServerMessage::Round {
+                round: args.next()?.parse().ok()?,
+                range: NumberRange::new(args.next()?.parse().ok()?, args.next()?.parse().ok()?)?,
+            }),
+            "FEEDBACK" => Some(ServerMessage::Feedback {
+                value: args.next()?.parse().ok()?,
+                hint: RaceHint::from_code(args.next()?)?,
+            }),
+            "SCORES" => {
+                let players = rest
+                    .split('|')
+                    .filter(|p| !p.is_empty())
+                    .map(|p| {
+                        let mut fields = p.splitn(4, ',');
+                        Some(RacePlayer {
+                            id: fields.next()?.parse().ok()?,
+                            attempts: fields.next()?.parse().ok()?,
+                            wins: fields.next()?.parse().ok()?,
+                            name: fields.next()?.to_string(),
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(ServerMessage::Scores(players))
+            }
+            "WINNER" => {
+                let (id, name) = rest.split_once(' ').unwrap_or((rest, ""));
+                Some(ServerMessage::Winner {
+                    id: id.parse().ok()?,
+                    name: name.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 名前に区切り文字が入るとSCORESが壊れるので取り除く
+fn sanitize_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| !matches!(c, '|' | ',' | '\n' | '\r'))
+        .take(16)
+        .collect();
+    if name.trim().is_empty() {
+        "名無し".to_string()
+    } else {
+        name
+    }
+}
+
+enum Outbox {
+    Local(Sender<ServerMessage>),
+    /// 書き込みは送信用スレッドに任せる。stream は切断するときだけ使う
+    Remote {
+        lines: Sender<String>,
+        stream: TcpStream,
+    },
+}
+
+struct ServerPlayer {
+    info: RacePlayer,
+    outbox: Outbox,
+}
+
+impl ServerPlayer {
+    fn send(&mut self, message: &ServerMessage) {
+        match &mut self.outbox {
+            Outbox::Local(sender) => {
+                let _ = sender.send(message.clone());
+            }
+            Outbox::Remote { lines, .. } => {
+                let _ = lines.send(message.to_line());
+            }
+        }
+    }
+}
+
+/// 1つのソケットに書き込むスレッドを立てる。送り先が詰まっても送る側は待たない
+fn spawn_writer(mut stream: TcpStream) -> Sender<String> {
+    let (sender, lines) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        for line in lines {
+            if writeln!(stream, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+    sender
+}
+
+/// ホストが持つ正解と判定。答えはクライアントに送らない
+struct RaceServer {
+    secret: Option<i64>,
+    range: NumberRange,
+    round: u32,
+    next_id: usize,
+    players: Vec<ServerPlayer>,
+    closed: bool,
+}
+
+impl RaceServer {
+    fn new(range: NumberRange) -> Self {
+        RaceServer {
+            secret: None,
+            range,
+            round: 0,
+            next_id: 0,
+            players: Vec::new(),
+            closed: false,
+        }
+    }
+
+    fn broadcast(&mut self, message: &ServerMessage) {
+        for player in &mut self.players {
+            player.send(message);
+        }
+    }
+
+    fn broadcast_scores(&mut self) {
+        let scores = ServerMessage::Scores(self.players.iter().map(|p| p.info.clone()).collect());
+        self.broadcast(&scores);
+    }
+
+    fn add_player(&mut self, name: &str, outbox: Outbox) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut player = ServerPlayer {
+            info: RacePlayer {
+                id,
+                name: sanitize_name(name),
+                attempts: 0,
+                wins: 0,
+            },
+            outbox,
+        };
+        player.send(&ServerMessage::Welcome { id });
+        if self.secret.is_some() {
+            player.send(&ServerMessage::Round {
+                round: self.round,
+                range: self.range,
+            });
+        }
+        self.players.push(player);
+        self.broadcast_scores();
+        id
+    }
+
+    fn remove_player(&mut self, id: usize) {
+        self.players.retain(|p| p.info.id != id);
+        self.broadcast_scores();
+    }
+
+    fn start_round(&mut self) {
+        self.round += 1;
+        self.secret = Some(rand::rng().random_range(self.range.min..=self.range.max));
+        for player in &mut self.players {
+            player.info.attempts = 0;
+        }
+        self.broadcast(&ServerMessage::Round {
+            round: self.round,
+            range: self.range,
+        });
+        self.broadcast_scores();
+    }
+
+    fn guess(&mut self, id: usize, value: i64) {
+        let hint = match self.secret {
+            Some(_) if !self.range.contains(value) => RaceHint::Rejected,
+            Some(secret) if value == secret => RaceHint::Correct,
+            Some(secret) if value > secret => RaceHint::TooBig,
+            Some(_) => RaceHint::TooSmall,
+            None => RaceHint::Rejected,
+        };
+        let Some(player) = self.players.iter_mut().find(|p| p.info.id == id) else {
+            return;
+        };
+        if hint != RaceHint::Rejected {
+            player.info.attempts += 1;
+        }
+        player.send(&ServerMessage::Feedback { value, hint });
+
+        // 最初に当てた人がラウンドの勝者。次のラウンドまで受け付けない
+        if hint == RaceHint::Correct {
+            player.info.wins += 1;
+            let name = player.info.name.clone();
+            self.secret = None;
+            self.broadcast(&ServerMessage::Winner { id, name });
+        }
+        self.broadcast_scores();
+    }
+}
+
+enum ConnectionKind {
+    Host {
+        server: Arc<Mutex<RaceServer>>,
+        port: u16,
+    },
+    Client {
+        stream: TcpStream,
+        outbox: Sender<String>,
+    },
+}
+
+/// LAN対戦のつながり。ホストは自分もプレイヤー0として参加する
+pub struct RaceConnection {
+    kind: ConnectionKind,
+    inbox: Receiver<ServerMessage>,
+}
+
+impl RaceConnection {
+    pub fn host(port: u16, name: &str, range: NumberRange) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let port = listener.local_addr()?.port();
+        let server = Arc::new(Mutex::new(RaceServer::new(range)));
+        let (sender, inbox) = mpsc::channel();
+        server
+            .lock()
+            .unwrap()
+            .add_player(name, Outbox::Local(sender));
+
+        let accept_server = server.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accept_server.lock().unwrap().closed {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let client_server = accept_server.clone();
+                thread::spawn(move || serve_client(stream, client_server));
+            }
+        });
+
+        Ok(RaceConnection {
+            kind: ConnectionKind::Host { server, port },
+            inbox,
+        })
+    }
+
+    /// 名前解決と接続で待つので、UIのスレッドからは呼ばない
+    pub fn join(address: &str, name: &str) -> io::Result<Self> {
+        let address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "アドレスが不正"))?;
+        let mut stream = TcpStream::connect_timeout(&address, Duration::from_secs(3))?;
+        writeln!(stream, "JOIN {}", sanitize_name(name))?;
+
+        let (sender, inbox) = mpsc::channel();
+        let reader = BufReader::new(stream.try_clone()?);
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Some(message) = ServerMessage::parse(&line)
+                    && sender.send(message).is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let outbox = spawn_writer(stream.try_clone()?);
+        Ok(RaceConnection {
+            kind: ConnectionKind::Client { stream, outbox },
+            inbox,
+        })
+    }
+
+    pub fn is_host(&self) -> bool {
+        matches!(self.kind, ConnectionKind::Host { .. })
+    }
+
+    pub fn inbox(&self) -> &Receiver<ServerMessage> {
+        &self.inbox
+    }
+
+    pub fn send_guess(&mut self, value: i64) {
+        match &mut self.kind {
+            ConnectionKind::Host { server, .. } => server.lock().unwrap().guess(0, value),
+            ConnectionKind::Client { outbox, .. } => {
+                let _ = outbox.send(format!("GUESS {}", value));
+            }
+        }
+    }
+
+    /// ホストだけが次のラウンドを始められる
+    pub fn start_round(&mut self) {
+        if let ConnectionKind::Host { server, .. } = &self.kind {
+            server.lock().unwrap().start_round();
+        }
+    }
+}
+
+impl Drop for RaceConnection {
+    fn drop(&mut self) {
+        match &self.kind {
+            ConnectionKind::Host { server, port } => {
+                let mut server = server.lock().unwrap();
+                server.closed = true;
+                for player in &mut server.players {
+                    if let Outbox::Remote { stream, .. } = &player.outbox {
+                        let _ = stream.shutdown(std::net::Shutdown::Both);
+                    }
+                }
+                drop(server);
+                // accept で止まっているスレッドを起こして終わらせる
+                let _ = TcpStream::connect(("127.0.0.1", *port));
+            }
+            ConnectionKind::Client { stream, .. } => {
+                let _ = stream.shutdown(std::net::Shutdown::Both);
+            }
+        }
+    }
+}
+
+/// クライアント1人分の受信ループ。最初の行で名前を受け取る
+fn serve_client(stream: TcpStream, server: Arc<Mutex<RaceServer>>) {
+    let (Ok(writer), Ok(shutdown)) = (stream.try_clone(), stream.try_clone()) else {
+        return;
+    };
+    let mut lines = BufReader::new(stream).lines();
+    let name = match lines.next() {
+        Some(Ok(line)) => match line.strip_prefix("JOIN ") {
+            Some(name) => name.to_string(),
+            None => return,
+        },
+        _ => return,
+    };
+    let outbox = Outbox::Remote {
+        lines: spawn_writer(writer),
+        stream: shutdown,
+    };
+    let id = server.lock().unwrap().add_player(&name, outbox);
+
+    for line in lines {
+        let Ok(line) = line else {
+            break;
+        };
+        if let Some(value) = line
+            .strip_prefix("GUESS ")
+            .and_then(|value| value.trim().parse::<i64>().ok())
+        {
+            server.lock().unwrap().guess(id, value);
+        }
+    }
+    server.lock().unwrap().remove_player(id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range() -> NumberRange {
+        NumberRange::new(1, 100).unwrap()
+    }
+
+    #[test]
+    fn messages_round_trip_through_lines() {
+        let messages = [
+            ServerMessage::Welcome { id: 3 },
+            ServerMessage::Round {
+                round: 2,
+                range: NumberRange::new(-50, 50).unwrap(),
+            },
+            ServerMessage::Feedback {
+                value: -7,
+                hint: RaceHint::TooSmall,
+            },
+            ServerMessage::Feedback {
+                value: 42,
+                hint: RaceHint::Rejected,
+            },
+            ServerMessage::Scores(vec![
+                RacePlayer {
+                    id: 0,
+                    name: "ホスト".to_string(),
+                    attempts: 4,
+                    wins: 1,
+                },
+                RacePlayer {
+                    id: 1,
+                    name: "guest player".to_string(),
+                    attempts: 0,
+                    wins: 0,
+                },
+            ]),
+            ServerMessage::Scores(Vec::new()),
+            ServerMessage::Winner {
+                id: 1,
+                name: "guest player".to_string(),
+            },
+        ];
+        for message in messages {
+            assert_eq!(ServerMessage::parse(&message.to_line()), Some(message));
+        }
+    }
+
+    #[test]
+    fn broken_lines_are_ignored() {
+        for line in ["", "HELLO", "WELCOME x", "ROUND 1 10 5", "FEEDBACK 5 MAYBE"] {
+            assert_eq!(ServerMessage::parse(line), None, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn sanitized_names_keep_scores_parseable() {
+        assert_eq!(sanitize_name("a|b,c\nd"), "abcd");
+        assert_eq!(sanitize_name("   "), "名無し");
+    }
+
+    #[test]
+    fn server_scores_guesses_and_first_correct_wins() {
+        let mut server = RaceServer::new(range());
+        let (host, host_inbox) = mpsc::channel();
+        let (guest, guest_inbox) = mpsc::channel();
+        let host_id = server.add_player("host", Outbox::Local(host));
+        let guest_id = server.add_player("guest", Outbox::Local(guest));
+
+        // ラウンド前の予想は受け付けない
+        server.guess(guest_id, 10);
+        server.start_round();
+        server.secret = Some(30);
+
+        server.guess(guest_id, 10);
+        server.guess(guest_id, 50);
+        server.guess(guest_id, 1000);
+        server.guess(host_id, 30);
+        server.guess(guest_id, 30);
+
+        let feedback = |inbox: &Receiver<ServerMessage>| -> Vec<(i64, RaceHint)> {
+            inbox
+                .try_iter()
+                .filter_map(|message| match message {
+                    ServerMessage::Feedback { value, hint } => Some((value, hint)),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(
+            feedback(&guest_inbox),
+            vec![
+                (10, RaceHint::Rejected),
+                (10, RaceHint::TooSmall),
+                (50, RaceHint::TooBig),
+                (1000, RaceHint::Rejected),
+                (30, RaceHint::Rejected),
+            ]
+        );
+        assert_eq!(feedback(&host_inbox), vec![(30, RaceHint::Correct)]);
+
+        let info = |server: &RaceServer, id: usize| {
+            server
+                .players
+                .iter()
+                .find(|p| p.info.id == id)
+                .map(|p| (p.info.attempts, p.info.wins))
+        };
+        assert_eq!(info(&server, guest_id), Some((2, 0)));
+        assert_eq!(info(&server, host_id), Some((1, 1)));
+        assert_eq!(server.secret, None);
+
+        // 次のラウンドで回数だけ戻る
+        server.start_round();
+        assert_eq!(info(&server, host_id), Some((0, 1)));
+        assert_eq!(server.round, 2);
+    }
+
+    #[test]
+    fn client_joins_and_guesses_over_loopback() {
+        let mut host = RaceConnection::host(0, "host", range()).unwrap();
+        let ConnectionKind::Host { server, port } = &host.kind else {
+            unreachable!();
+        };
+        let (server, port) = (server.clone(), *port);
+        let mut client = RaceConnection::join(&format!("127.0.0.1:{}", port), "guest").unwrap();
+        let next = |connection: &RaceConnection| {
+            connection
+                .inbox()
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+        };
+        assert_eq!(next(&client), ServerMessage::Welcome { id: 1 });
+
+        host.start_round();
+        server.lock().unwrap().secret = Some(60);
+        client.send_guess(60);
+        loop {
+            if let ServerMessage::Winner { id, name } = next(&client) {
+                assert_eq!((id, name.as_str()), (1, "guest"));
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use crate::models::difficulty::NumberRange;
+use crate::models::number_format::{group, parse_grouped};
+use crate::models::race_net::{DEFAULT_PORT, RaceConnection, RaceHint, RacePlayer, ServerMessage};
+use gpui::{AsyncApp, Context, SharedString, Task, WeakEntity};
+use std::sync::mpsc::TryRecvError;
+use std::time::Duration;
+
+/// LAN対戦のロビーと対戦中の画面の状態
+pub struct RaceState {
+    pub name_input: SharedString,
+    pub address_input: SharedString,
+    pub editing_address: bool,
+    pub host_range: NumberRange,
+    pub connection: Option<RaceConnection>,
+    pub my_id: Option<usize>,
+    pub round: u32,
+    pub range: Option<NumberRange>,
+    pub current_input: SharedString,
+    pub last_feedback: Option<(i64, RaceHint)>,
+    pub players: Vec<RacePlayer>,
+    pub winner: Option<(usize, String)>,
+    pub message: SharedString,
+    pub _poll_task: Option<Task<()>>,
+    _join_task: Option<Task<()>>,
+}
+
+impl RaceState {
+    pub fn new(host_range: NumberRange) -> Self {
+        RaceState {
+            name_input: "Player".into(),
+            address_input: format!("127.0.0.1:{}", DEFAULT_PORT).into(),
+            editing_address: true,
+            host_range,
+            connection: None,
+            my_id: None,
+            round: 0,
+            range: None,
+            current_input: String::new().into(),
+            last_feedback: None,
+            players: Vec::new(),
+            winner: None,
+            message: String::new().into(),
+            _poll_task: None,
+            _join_task: None,
+        }
+    }
+
+    pub fn is_host(&self) -> bool {
+        self.connection.as_ref().is_some_and(|c| c.is_host())
+    }
+
+    pub fn open_lobby(&mut self, host_range: NumberRange, cx: &mut Context<Self>) {
+        self.host_range = host_range;
+        cx.notify();
+    }
+
+    fn editing_input(&mut self) -> &mut SharedString {
+        if self.editing_address {
+            &mut self.address_input
+        } else {
+            &mut self.name_input
+        }
+    }
+
+    pub fn push_char(&mut self, c: &str, cx: &mut Context<Self>) {
+        let input = self.editing_input();
+        *input = format!("{}{}", input, c).into();
+        cx.notify();
+    }
+
+    pub fn pop_char(&mut self, cx: &mut Context<Self>) {
+        let input = self.editing_input();
+        let mut chars = input.chars();
+        chars.next_back();
+        *input = chars.as_str().to_string().into();
+        cx.notify();
+    }
+
+    pub fn select_field(&mut self, editing_address: bool, cx: &mut Context<Self>) {
+        self.editing_address = editing_address;
+        cx.notify();
+    }
+
+    pub fn toggle_field(&mut self, cx: &mut Context<Self>) {
+        self.editing_address = !self.editing_address;
+        cx.notify();
+    }
+
+    /// アドレス欄の末尾のポート番号で待ち受ける
+    pub fn host(&mut self, cx: &mut Context<Self>) {
+        let port = self
+            .address_input
+            .rsplit(':')
+            .next()
+            .and_then(|port| port.parse::<u16>().ok())
+            .unwrap_or(DEFAULT_PORT);
+        match RaceConnection::host(port, &self.name_input, self.host_range) {
+            Ok(connection) => {
+                self.connected(connection, cx);
+                self.message = format!("ポート {} で待ち受け中", port).into();
+            }
+            Err(err) => self.message = format!("ホストできない: {}", err).into(),
+        }
+        cx.notify();
+    }
+
+    /// 接続は待ち時間があるので裏で行い、終わったら取り込む
+    pub fn join(&mut self, cx: &mut Context<Self>) {
+        if self._join_task.is_some() {
+            return;
+        }
+        let address = self.address_input.to_string();
+        let name = self.name_input.to_string();
+        let connecting = cx
+            .background_executor()
+            .spawn(async move { RaceConnection::join(&address, &name) });
+        self.message = "接続中…".into();
+        self._join_task = Some(
+            cx.spawn(|we: WeakEntity<RaceState>, cx_ref: &mut AsyncApp| {
+                let mut cx = cx_ref.clone();
+                async move {
+                    let result = connecting.await;
+                    let _ = we.update(&mut cx, |this, model_cx| {
+                        this._join_task = None;
+                        match result {
+                            Ok(connection) => {
+                                this.connected(connection, model_cx);
+                                this.message = "ホストが始めるのを待っています".into();
+                            }
+                            Err(err) => this.message = format!("参加できない: {}", err).into(),
+                        }
+                        model_cx.notify();
+                    });
+                }
+            }),
+        );
+        cx.notify();
+    }
+
+    fn connected(&mut self, connection: RaceConnection, cx: &mut Context<Self>) {
+        self.connection = Some(connection);
+        self.my_id = None;
+        self.round = 0;
+        self.range = None;
+        self.current_input = String::new().into();
+        self.last_feedback = None;
+        self.players.clear();
+        self.winner = None;
+        self.poll_timer(cx);
+    }
+
+    pub fn leave(&mut self, cx: &mut Context<Self>) {
+        self.connection = None;
+        self._poll_task = None;
+        self._join_task = None;
+        self.players.clear();
+        self.message = String::new().into();
+        cx.notify();
+    }
+
+    pub fn start_round(&mut self, cx: &mut Context<Self>) {
+        if let Some(connection) = &mut self.connection {
+            connection.start_round();
+        }
+        cx.notify();
+    }
+
+    pub fn push_digit(&mut self, key: &str, cx: &mut Context<Self>) {
+        let (max_chars, negative) = self
+            .range
+            .map_or((20, true), |range| (range.max_chars(), range.min < 0));
+        let accepted = key != "-" || (self.current_input.is_empty() && negative);
+        if accepted && self.current_input.len() < max_chars {
+            self.current_input = format!("{}{}", self.current_input, key).into();
+        }
+        cx.notify();
+    }
+
+    pub fn pop_digit(&mut self, cx: &mut Context<Self>) {
+        let mut chars = self.current_input.chars();
+        chars.next_back();
+        self.current_input = chars.as_str().to_string().into();
+        cx.notify();
+    }
+
+    /// 判定はホストがするので、ここでは送るだけ
+    pub fn submit_guess(&mut self, cx: &mut Context<Self>) {
+        let Some(value) = parse_grouped(&self.current_input) else {
+            return;
+        };
+        if let Some(connection) = &mut self.connection {
+            connection.send_guess(value);
+        }
+        self.current_input = String::new().into();
+        cx.notify();
+    }
+
+    fn handle_message(&mut self, message: ServerMessage) {
+        match message {
+            ServerMessage::Welcome { id } => self.my_id = Some(id),
+            ServerMessage::Round { round, range } => {
+                self.round = round;
+                self.range = Some(range);
+                self.last_feedback = None;
+                self.winner = None;
+                self.message = format!("Round {} スタート！ {}", round, range).into();
+            }
+            ServerMessage::Feedback { value, hint } => {
+                self.last_feedback = Some((value, hint));
+                self.message = match hint {
+                    RaceHint::TooBig => format!("{} はもっと小さい", group(value)),
+                    RaceHint::TooSmall => format!("{} はもっと大きい", group(value)),
+                    RaceHint::Correct => format!("{} で正解！", group(value)),
+                    RaceHint::Rejected => format!("{} は受け付けられない", group(value)),
+                }
+                .into();
+            }
+            ServerMessage::Scores(players) => self.players = players,
+            ServerMessage::Winner { id, name } => {
+                self.message = if Some(id) == self.my_id {
+                    "あなたの勝ち！".into()
+                } else {
+                    format!("{} の勝ち", name).into()
+                };
+                self.winner = Some((id, name));
+            }
+        }
+    }
+
+    /// 受信したメッセージを定期的に取り込む
+    fn poll_timer(&mut self, cx: &mut Context<RaceState>) {
+        self._poll_task = Some(
+            cx.spawn(|we: WeakEntity<RaceState>, cx_ref: &mut AsyncApp| {
+                let mut cx = cx_ref.clone();
+                async move {
+                    loop {
+                        cx.background_executor()
+                            .timer(Duration::from_millis(100))
+                            .await;
+                        let should_stop = we
+                            .update(&mut cx, |this, model_cx| {
+                                let Some(connection) = &this.connection else {
+                                    return true;
+                                };
+                                let mut messages = Vec::new();
+                                let disconnected = loop {
+                                    match connection.inbox().try_recv() {
+                                        Ok(message) => messages.push(message),
+                                        Err(TryRecvError::Empty) => break false,
+                                        Err(TryRecvError::Disconnected) => break true,
+                                    }
+                                };
+                                let changed = !messages.is_empty() || disconnected;
+                                for message in messages {
+                                    this.handle_message(message);
+                                }
+                                if disconnected {
+                                    this.connection = None;
+                                    this.message = "接続が切れました".into();
+                                }
+                                if changed {
+                                    model_cx.notify();
+                                }
+                                disconnected
+                            })
+                            .unwrap_or(true);
+
+                        if should_stop {
+                            break;
+                        }
+                    }
+                }
+            }),
+        );
+    }
+}
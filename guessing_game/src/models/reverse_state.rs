@@ -0,0 +1,160 @@
+use crate::models::difficulty::NumberRange;
+use crate::models::number_format::group;
+use gpui::Context;
+use gpui::SharedString;
+use rand::Rng;
+
+/// プレイヤーの答え。答えの数がコンピュータの予想より大きいか小さいか
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Reply {
+    Higher,
+    Lower,
+    Correct,
+}
+
+/// コンピュータの予想のしかた
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GuessStrategy {
+    Bisection,
+    Linear,
+    Random,
+}
+
+impl GuessStrategy {
+    pub const ALL: [GuessStrategy; 3] = [
+        GuessStrategy::Bisection,
+        GuessStrategy::Linear,
+        GuessStrategy::Random,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GuessStrategy::Bisection => "二分探索",
+            GuessStrategy::Linear => "小さい順",
+            GuessStrategy::Random => "ランダム",
+        }
+    }
+
+    /// 残っている区間 min..=max から次の予想を選ぶ
+    pub fn next_guess(&self, min: i64, max: i64) -> i64 {
+        match self {
+            GuessStrategy::Bisection => NumberRange { min, max }.midpoint(),
+            GuessStrategy::Linear => min,
+            GuessStrategy::Random => rand::rng().random_range(min..=max),
+        }
+    }
+}
+
+/// プレイヤーが数を決めて、コンピュータが当てるモード
+pub struct ReverseState {
+    pub range: NumberRange,
+    pub strategy: GuessStrategy,
+    pub low: i64,
+    pub high: i64,
+    pub current_guess: i64,
+    pub answers: Vec<(i64, Reply)>,
+    pub finished: bool,
+    pub message: SharedString,
+}
+
+impl ReverseState {
+    pub fn new(range: NumberRange) -> Self {
+        let strategy = GuessStrategy::Bisection;
+        ReverseState {
+            range,
+            strategy,
+            low: range.min,
+            high: range.max,
+            current_guess: strategy.next_guess(range.min, range.max),
+            answers: Vec::new(),
+            finished: false,
+            message: String::new().into(),
+        }
+    }
+
+    pub fn start(&mut self, range: NumberRange, cx: &mut Context<Self>) {
+        self.range = range;
+        self.restart(cx);
+    }
+
+    pub fn restart(&mut self, cx: &mut Context<Self>) {
+        self.low = self.range.min;
+        self.high = self.range.max;
+        self.current_guess = self.strategy.next_guess(self.low, self.high);
+        self.answers.clear();
+        self.finished = false;
+        self.message = String::new().into();
+        cx.notify();
+    }
+
+    pub fn cycle_strategy(&mut self, cx: &mut Context<Self>) {
+        let index = GuessStrategy::ALL
+            .iter()
+            .position(|strategy| *strategy == self.strategy)
+            .unwrap_or(0);
+        self.strategy = GuessStrategy::ALL[(index + 1) % GuessStrategy::ALL.len()];
+        self.restart(cx);
+    }
+
+    pub fn answer(&mut self, reply: Reply, cx: &mut Context<Self>) {
+        if self.finished {
+            return;
+        }
+        let guess = self.current_guess;
+        let (low, high) = match reply {
+            Reply::Correct => (guess, guess),
+            Reply::Higher => (guess.saturating_add(1).max(self.low), self.high),
+            Reply::Lower => (self.low, guess.saturating_sub(1).min(self.high)),
+        };
+        // 端の数で大きい/小さいと言われたときも区間が空になる
+        let empty = low > high
+            || (reply == Reply::Higher && guess == i64::MAX)
+            || (reply == Reply::Lower && guess == i64::MIN);
+        if empty {
+            self.message = self.contradiction(guess, reply).into();
+            cx.notify();
+            return;
+        }
+
+        self.answers.push((guess, reply));
+        self.low = low;
+        self.high = high;
+        if reply == Reply::Correct {
+            self.finished = true;
+            self.message = format!("{}回で当てた！", self.answers.len()).into();
+        } else {
+            self.current_guess = self.strategy.next_guess(low, high);
+            self.message = String::new().into();
+        }
+        cx.notify();
+    }
+
+    /// 矛盾の原因になったこれまでの答えを探して説明する
+    fn contradiction(&self, guess: i64, reply: Reply) -> String {
+        let conflict = match reply {
+            Reply::Higher => self
+                .answers
+                .iter()
+                .filter(|(value, r)| *r == Reply::Lower && *value <= guess.saturating_add(1))
+                .map(|(value, _)| *value)
+                .min()
+                .map(|value| format!("「{}より小さい」", group(value))),
+            Reply::Lower => self
+                .answers
+                .iter()
+                .filter(|(value, r)| *r == Reply::Higher && value.saturating_add(1) >= guess)
+                .map(|(value, _)| *value)
+                .max()
+                .map(|value| format!("「{}より大きい」", group(value))),
+            Reply::Correct => None,
+        };
+        let said = match reply {
+            Reply::Higher => format!("「{}より大きい」", group(guess)),
+            _ => format!("「{}より小さい」", group(guess)),
+        };
+        match conflict {
+            Some(conflict) => format!("{}と{}は矛盾しているよ", conflict, said),
+            None => format!("{}だと {} の中に当てはまる数がないよ", said, self.range),
+        }
+    }
+}
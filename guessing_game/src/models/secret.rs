@@ -0,0 +1,142 @@
+use crate::models::difficulty::NumberRange;
+use crate::models::game_state::GuessOutcome;
+use rand::Rng;
+
+/// 予想に正直に答える、隠された答え
+pub trait Secret {
+    /// guess を答えと比べる
+    fn compare(&mut self, guess: i64) -> GuessOutcome;
+    /// 決着がついたときに明かす数。それまでの正直な答えすべてと矛盾しない
+    fn reveal(&self) -> i64;
+}
+
+/// 最初に決めたまま変わらない答え
+pub struct FixedSecret(pub i64);
+
+impl Secret for FixedSecret {
+    fn compare(&mut self, guess: i64) -> GuessOutcome {
+        GuessOutcome::of(guess, self.0)
+    }
+
+    fn reveal(&self) -> i64 {
+        self.0
+    }
+}
+
+/// 答えを決めずに、それまでの答えと矛盾しない数の範囲だけを持つ。
+/// 予想されるたびに、残る数が多いほうへ答えを寄せる
+pub struct EvilSecret {
+    pub candidates: NumberRange,
+}
+
+impl EvilSecret {
+    pub fn new(range: NumberRange) -> Self {
+        EvilSecret { candidates: range }
+    }
+}
+
+impl Secret for EvilSecret {
+    fn compare(&mut self, guess: i64) -> GuessOutcome {
+        let NumberRange { min, max } = self.candidates;
+        if guess < min {
+            return GuessOutcome::TooLow;
+        }
+        if guess > max {
+            return GuessOutcome::TooHigh;
+        }
+        // 端どうしの範囲でもあふれないように i128 で比べる
+        let (below, above) = (guess as i128 - min as i128, max as i128 - guess as i128);
+        if below == 0 && above == 0 {
+            GuessOutcome::Correct
+        } else if above >= below {
+            self.candidates.min = guess + 1;
+            GuessOutcome::TooLow
+        } else {
+            self.candidates.max = guess - 1;
+            GuessOutcome::TooHigh
+        }
+    }
+
+    fn reveal(&self) -> i64 {
+        self.candidates.min
+    }
+}
+
+/// 設定画面で選ぶ答えの決め方
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SecretKind {
+    Random,
+    Evil,
+}
+
+impl SecretKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SecretKind::Random => "答え: ランダム",
+            SecretKind::Evil => "答え: 後出し",
+        }
+    }
+
+    pub fn build(&self, range: NumberRange) -> Box<dyn Secret> {
+        match self {
+            SecretKind::Random => {
+                Box::new(FixedSecret(rand::rng().random_range(range.min..=range.max)))
+            }
+            SecretKind::Evil => Box::new(EvilSecret::new(range)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    /// どんな順で予想されても、最後に明かす数がそれまでの答えすべてと合っている
+    #[test]
+    fn evil_secret_answers_stay_honest_for_the_revealed_number() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let range = NumberRange::new(-50, 50).unwrap();
+        for _ in 0..200 {
+            let mut secret = EvilSecret::new(range);
+            let mut answers = Vec::new();
+            for _ in 0..30 {
+                let guess = rng.random_range(-60..=60);
+                let outcome = secret.compare(guess);
+                answers.push((guess, outcome));
+                assert!(secret.candidates.min <= secret.candidates.max);
+                if outcome == GuessOutcome::Correct {
+                    break;
+                }
+            }
+            let revealed = secret.reveal();
+            assert!(range.contains(revealed));
+            for (guess, outcome) in answers {
+                assert_eq!(GuessOutcome::of(guess, revealed), outcome);
+            }
+        }
+    }
+
+    #[test]
+    fn evil_secret_keeps_the_larger_side() {
+        let mut secret = EvilSecret::new(NumberRange::new(1, 100).unwrap());
+        assert_eq!(secret.compare(30), GuessOutcome::TooLow);
+        assert_eq!(secret.candidates, NumberRange::new(31, 100).unwrap());
+        assert_eq!(secret.compare(90), GuessOutcome::TooHigh);
+        assert_eq!(secret.candidates, NumberRange::new(31, 89).unwrap());
+        // 1つに絞られるまで正解にはならない
+        let mut secret = EvilSecret::new(NumberRange::new(5, 6).unwrap());
+        assert_eq!(secret.compare(5), GuessOutcome::TooLow);
+        assert_eq!(secret.compare(6), GuessOutcome::Correct);
+    }
+
+    #[test]
+    fn evil_secret_handles_the_full_i64_range() {
+        let mut secret = EvilSecret::new(NumberRange::new(i64::MIN, i64::MAX).unwrap());
+        assert_eq!(secret.compare(i64::MIN), GuessOutcome::TooLow);
+        assert_eq!(secret.compare(i64::MAX), GuessOutcome::TooHigh);
+        assert_eq!(secret.candidates.min, i64::MIN + 1);
+        assert_eq!(secret.candidates.max, i64::MAX - 1);
+    }
+}
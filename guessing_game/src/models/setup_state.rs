@@ -0,0 +1,263 @@
+use crate::models::bulls_cows_state::{BullsCowsSettings, CODE_ATTEMPTS, CODE_LENGTHS};
+use crate::models::difficulty::NumberRange;
+use crate::models::number_format::parse_grouped;
+use crate::models::oracle::LiarKind;
+use crate::models::secret::SecretKind;
+use crate::models::speed_run_state::SPEED_RUN_ROUNDS;
+use gpui::Context;
+use gpui::SharedString;
+use std::time::Duration;
+
+/// タイトル画面で選ぶゲームの種類
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GameKind {
+    Number,
+    BullsAndCows,
+}
+
+impl GameKind {
+    pub const ALL: [GameKind; 2] = [GameKind::Number, GameKind::BullsAndCows];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameKind::Number => "数当て",
+            GameKind::BullsAndCows => "Bulls & Cows",
+        }
+    }
+}
+
+/// 設定画面で選べる遊び方
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PlayMode {
+    Solo,
+    Reverse,
+    HotSeat,
+    Race,
+    SpeedRun,
+}
+
+impl PlayMode {
+    pub const ALL: [PlayMode; 5] = [
+        PlayMode::Solo,
+        PlayMode::Reverse,
+        PlayMode::HotSeat,
+        PlayMode::Race,
+        PlayMode::SpeedRun,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlayMode::Solo => "自分で当てる",
+            PlayMode::Reverse => "コンピュータが当てる",
+            PlayMode::HotSeat => "ふたりで対戦",
+            PlayMode::Race => "LANで早当て",
+            PlayMode::SpeedRun => "スピードラン",
+        }
+    }
+}
+
+/// 対戦のラウンド数の候補
+pub const HOT_SEAT_ROUNDS: [u32; 3] = [1, 3, 5];
+
+/// 1ラウンドの制限時間(秒)の候補。0は制限なし
+pub const TIME_LIMITS: [u32; 4] = [0, 30, 60, 120];
+
+/// 1ゲームでつかれる嘘の上限の候補
+pub const LIE_COUNTS: [u32; 4] = [0, 1, 2, 3];
+
+/// 設定画面で入力中の自由範囲
+pub struct SetupState {
+    pub game_kind: GameKind,
+    pub bulls_cows: BullsCowsSettings,
+    pub min_input: SharedString,
+    pub max_input: SharedString,
+    pub editing_max: bool,
+    pub limit_attempts: bool,
+    pub mode: PlayMode,
+    pub rounds: u32,
+    pub speed_run_rounds: u32,
+    pub time_limit_secs: u32,
+    pub lies: u32,
+    pub liar: LiarKind,
+    pub secret_kind: SecretKind,
+    pub message: SharedString,
+}
+
+impl SetupState {
+    pub fn new() -> Self {
+        SetupState {
+            game_kind: GameKind::Number,
+            bulls_cows: BullsCowsSettings::default(),
+            min_input: "1".into(),
+            max_input: String::new().into(),
+            editing_max: true,
+            limit_attempts: true,
+            mode: PlayMode::Solo,
+            rounds: HOT_SEAT_ROUNDS[1],
+            speed_run_rounds: SPEED_RUN_ROUNDS[1],
+            time_limit_secs: TIME_LIMITS[0],
+            lies: LIE_COUNTS[0],
+            liar: LiarKind::Random,
+            secret_kind: SecretKind::Random,
+            message: String::new().into(),
+        }
+    }
+
+    fn editing_input(&mut self) -> &mut SharedString {
+        if self.editing_max {
+            &mut self.max_input
+        } else {
+            &mut self.min_input
+        }
+    }
+
+    pub fn push_digit(&mut self, key: &str, cx: &mut Context<Self>) {
+        let input = self.editing_input();
+        // 符号を含めてi64に収まる文字数まで
+        let accepted = key != "-" || input.is_empty();
+        if accepted && input.len() < 20 {
+            *input = format!("{}{}", input, key).into();
+        }
+        cx.notify();
+    }
+
+    pub fn pop_digit(&mut self, cx: &mut Context<Self>) {
+        let input = self.editing_input();
+        let mut chars = input.chars();
+        chars.next_back();
+        *input = chars.as_str().to_string().into();
+        cx.notify();
+    }
+
+    pub fn toggle_field(&mut self, cx: &mut Context<Self>) {
+        self.editing_max = !self.editing_max;
+        cx.notify();
+    }
+
+    pub fn toggle_limit_attempts(&mut self, cx: &mut Context<Self>) {
+        self.limit_attempts = !self.limit_attempts;
+        cx.notify();
+    }
+
+    pub fn select_game_kind(&mut self, game_kind: GameKind, cx: &mut Context<Self>) {
+        self.game_kind = game_kind;
+        self.message = String::new().into();
+        cx.notify();
+    }
+
+    pub fn cycle_code_length(&mut self, cx: &mut Context<Self>) {
+        let index = CODE_LENGTHS
+            .iter()
+            .position(|length| *length == self.bulls_cows.length)
+            .unwrap_or(0);
+        self.bulls_cows.length = CODE_LENGTHS[(index + 1) % CODE_LENGTHS.len()];
+        cx.notify();
+    }
+
+    pub fn toggle_code_repeats(&mut self, cx: &mut Context<Self>) {
+        self.bulls_cows.allow_repeats = !self.bulls_cows.allow_repeats;
+        cx.notify();
+    }
+
+    pub fn cycle_code_attempts(&mut self, cx: &mut Context<Self>) {
+        let index = CODE_ATTEMPTS
+            .iter()
+            .position(|attempts| *attempts == self.bulls_cows.max_attempts)
+            .unwrap_or(0);
+        self.bulls_cows.max_attempts = CODE_ATTEMPTS[(index + 1) % CODE_ATTEMPTS.len()];
+        cx.notify();
+    }
+
+    pub fn cycle_mode(&mut self, cx: &mut Context<Self>) {
+        let index = PlayMode::ALL
+            .iter()
+            .position(|mode| *mode == self.mode)
+            .unwrap_or(0);
+        self.mode = PlayMode::ALL[(index + 1) % PlayMode::ALL.len()];
+        cx.notify();
+    }
+
+    pub fn cycle_rounds(&mut self, cx: &mut Context<Self>) {
+        let index = HOT_SEAT_ROUNDS
+            .iter()
+            .position(|rounds| *rounds == self.rounds)
+            .unwrap_or(0);
+        self.rounds = HOT_SEAT_ROUNDS[(index + 1) % HOT_SEAT_ROUNDS.len()];
+        cx.notify();
+    }
+
+    pub fn cycle_speed_run_rounds(&mut self, cx: &mut Context<Self>) {
+        let index = SPEED_RUN_ROUNDS
+            .iter()
+            .position(|rounds| *rounds == self.speed_run_rounds)
+            .unwrap_or(0);
+        self.speed_run_rounds = SPEED_RUN_ROUNDS[(index + 1) % SPEED_RUN_ROUNDS.len()];
+        cx.notify();
+    }
+
+    pub fn cycle_time_limit(&mut self, cx: &mut Context<Self>) {
+        let index = TIME_LIMITS
+            .iter()
+            .position(|secs| *secs == self.time_limit_secs)
+            .unwrap_or(0);
+        self.time_limit_secs = TIME_LIMITS[(index + 1) % TIME_LIMITS.len()];
+        cx.notify();
+    }
+
+    pub fn time_limit(&self) -> Option<Duration> {
+        (self.time_limit_secs > 0).then(|| Duration::from_secs(self.time_limit_secs as u64))
+    }
+
+    pub fn cycle_lies(&mut self, cx: &mut Context<Self>) {
+        let index = LIE_COUNTS
+            .iter()
+            .position(|lies| *lies == self.lies)
+            .unwrap_or(0);
+        self.lies = LIE_COUNTS[(index + 1) % LIE_COUNTS.len()];
+        cx.notify();
+    }
+
+    pub fn toggle_liar(&mut self, cx: &mut Context<Self>) {
+        self.liar = match self.liar {
+            LiarKind::Random => LiarKind::Adversarial,
+            LiarKind::Adversarial => LiarKind::Random,
+        };
+        cx.notify();
+    }
+
+    pub fn toggle_secret_kind(&mut self, cx: &mut Context<Self>) {
+        self.secret_kind = match self.secret_kind {
+            SecretKind::Random => SecretKind::Evil,
+            SecretKind::Evil => SecretKind::Random,
+        };
+        cx.notify();
+    }
+
+    pub fn select_field(&mut self, editing_max: bool, cx: &mut Context<Self>) {
+        self.editing_max = editing_max;
+        cx.notify();
+    }
+
+    /// 入力が正しければ範囲を返し、だめならメッセージを出す
+    pub fn custom_range(&mut self, cx: &mut Context<Self>) -> Option<NumberRange> {
+        let range = match (
+            parse_grouped(&self.min_input),
+            parse_grouped(&self.max_input),
+        ) {
+            (Some(min), Some(max)) => NumberRange::new(min, max),
+            _ => None,
+        };
+        self.message = match range {
+            Some(_) => String::new().into(),
+            None => "最小 < 最大 になるように入力してね".into(),
+        };
+        cx.notify();
+        range
+    }
+}
+
+impl Default for SetupState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
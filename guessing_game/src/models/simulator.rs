@@ -0,0 +1,113 @@
+use crate::models::difficulty::{Difficulty, NumberRange};
+use crate::models::game_state::{GameState, GuessOutcome};
+use crate::models::strategy::Strategy;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// ひとつの作戦で何回も遊んだ結果
+pub struct SimulationReport {
+    pub strategy: &'static str,
+    pub range: NumberRange,
+    pub games: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub worst: u32,
+    /// 回数ごとのゲーム数
+    pub distribution: BTreeMap<u32, usize>,
+}
+
+/// 窓もgpuiのコンテキストも使わずに GameState の判定で遊ぶ
+pub fn simulate(
+    strategy: &mut dyn Strategy,
+    range: NumberRange,
+    games: usize,
+    seed: u64,
+) -> SimulationReport {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut game = GameState::new(Difficulty::Custom(range));
+    game.set_difficulty(Difficulty::Custom(range), false);
+
+    let mut counts: Vec<u32> = Vec::with_capacity(games);
+    for _ in 0..games {
+        game.reload_with_secret(rng.random_range(range.min..=range.max));
+        // 受け付けられない予想ばかり返す作戦でも終わるように上限をつける
+        let limit = range.size().min(u64::MAX as u128) as u64;
+        let mut tries = 0u64;
+        loop {
+            let guess = strategy.next_guess(game.possible_range(), &mut rng);
+            tries += 1;
+            if matches!(game.submit(&guess.to_string()), Ok(GuessOutcome::Correct))
+                || tries >= limit
+            {
+                break;
+            }
+        }
+        counts.push(game.attempts);
+    }
+
+    counts.sort_unstable();
+    let mut distribution = BTreeMap::new();
+    for &count in &counts {
+        *distribution.entry(count).or_insert(0) += 1;
+    }
+    let median = match counts.len() {
+        0 => 0.0,
+        n if n % 2 == 0 => (counts[n / 2 - 1] + counts[n / 2]) as f64 / 2.0,
+        n => counts[n / 2] as f64,
+    };
+    SimulationReport {
+        strategy: strategy.name(),
+        range,
+        games,
+        mean: counts.iter().map(|&c| c as f64).sum::<f64>() / games.max(1) as f64,
+        median,
+        worst: counts.last().copied().unwrap_or(0),
+        distribution,
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} ({}, {} games): mean {:.2}, median {:.1}, worst {}",
+            self.strategy, self.range, self.games, self.mean, self.median, self.worst
+        )?;
+        let most = self.distribution.values().copied().max().unwrap_or(1);
+        for (attempts, games) in &self.distribution {
+            let bar = "#".repeat((games * 40).div_ceil(most));
+            writeln!(f, "  {:>3} | {:<40} {}", attempts, bar, games)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::strategy::{Bisection, all_strategies};
+
+    #[test]
+    fn same_seed_gives_the_same_report() {
+        let range = NumberRange::new(1, 1000).unwrap();
+        for (mut first, mut second) in all_strategies().into_iter().zip(all_strategies()) {
+            let a = simulate(first.as_mut(), range, 300, 42);
+            let b = simulate(second.as_mut(), range, 300, 42);
+            assert_eq!(a.distribution, b.distribution);
+            assert_eq!(a.mean, b.mean);
+            assert_eq!(a.distribution.values().sum::<usize>(), 300);
+        }
+    }
+
+    #[test]
+    fn bisection_never_needs_more_than_the_optimal_attempts() {
+        for (min, max) in [(1, 100), (-500, 500), (i64::MIN, i64::MAX)] {
+            let range = NumberRange::new(min, max).unwrap();
+            let report = simulate(&mut Bisection, range, 200, 1);
+            assert!(report.worst <= range.optimal_attempts(), "{}", report);
+            assert!(report.median <= report.worst as f64);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::models::game_state::{GameState, GameStatus};
+use gpui::Context;
+use std::time::{Duration, Instant};
+
+/// スピードランのラウンド数の候補
+pub const SPEED_RUN_ROUNDS: [u32; 3] = [3, 5, 10];
+/// 1回の予想を何秒ぶんとして数えるか
+pub const GUESS_PENALTY_SECS: u64 = 5;
+
+/// 1ラウンド分の結果
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct SpeedRunRound {
+    pub attempts: u32,
+    pub won: bool,
+    pub elapsed: Duration,
+}
+
+/// 何ラウンドかを続けて遊び、通しの時間と予想の回数を競う
+pub struct SpeedRunState {
+    pub active: bool,
+    pub rounds: u32,
+    pub results: Vec<SpeedRunRound>,
+    pub started_at: Instant,
+    pub finished_at: Option<Instant>,
+    /// 同じラウンドを二重に数えないように、最後に記録したラウンドの開始時刻を覚えておく
+    recorded_game: Option<Instant>,
+}
+
+impl SpeedRunState {
+    pub fn new() -> Self {
+        SpeedRunState {
+            active: false,
+            rounds: SPEED_RUN_ROUNDS[0],
+            results: Vec::new(),
+            started_at: Instant::now(),
+            finished_at: None,
+            recorded_game: None,
+        }
+    }
+
+    pub fn start(&mut self, rounds: u32, cx: &mut Context<Self>) {
+        self.active = true;
+        self.rounds = rounds;
+        self.results.clear();
+        self.started_at = Instant::now();
+        self.finished_at = None;
+        self.recorded_game = None;
+        cx.notify();
+    }
+
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        self.active = false;
+        cx.notify();
+    }
+
+    /// 全ラウンドを終えたか
+    pub fn is_complete(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    /// 時計が進んでいるか。ラウンドの合間も止まらない
+    pub fn is_running(&self) -> bool {
+        self.active && !self.is_complete()
+    }
+
+    /// いま遊んでいるラウンド(1から)
+    pub fn current_round(&self) -> u32 {
+        (self.results.len() as u32 + 1).min(self.rounds)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.finished_at.unwrap_or_else(Instant::now) - self.started_at
+    }
+
+    pub fn total_attempts(&self) -> u32 {
+        self.results.iter().map(|round| round.attempts).sum()
+    }
+
+    /// 時間と、予想1回ごとの加算を足したタイム。少ないほど良い
+    pub fn score(&self) -> Duration {
+        self.elapsed() + Duration::from_secs(self.total_attempts() as u64 * GUESS_PENALTY_SECS)
+    }
+
+    /// 決着がついたラウンドを記録する。記録したら true
+    pub fn finish_round(&mut self, game: &GameState) -> bool {
+        if !self.is_running()
+            || game.status == GameStatus::Playing
+            || self.recorded_game == Some(game.started_at)
+        {
+            return false;
+        }
+        self.recorded_game = Some(game.started_at);
+        self.results.push(SpeedRunRound {
+            attempts: game.attempts,
+            won: game.status == GameStatus::Won,
+            elapsed: game.elapsed(),
+        });
+        if self.results.len() as u32 >= self.rounds {
+            self.finished_at = Some(Instant::now());
+        }
+        true
+    }
+}
+
+impl Default for SpeedRunState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
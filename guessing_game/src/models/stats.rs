@@ -0,0 +1,378 @@
+use crate::models::difficulty::Difficulty;
+use crate::models::game_state::{GameState, GameStatus};
+use crate::models::secret::SecretKind;
+use crate::storage;
+use chrono::{Local, NaiveDate};
+use gpui::{Context, SharedString};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// 保存するファイルの形式のバージョン
+pub const STATS_VERSION: u32 = 1;
+/// 難易度ごとに残す順位の数
+pub const LEADERBOARD_SIZE: usize = 10;
+/// 名前の最大文字数
+pub const NAME_MAX_CHARS: usize = 12;
+
+/// ランキングの1行
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct ScoreEntry {
+    pub name: String,
+    pub attempts: u32,
+    pub millis: u64,
+    pub date: NaiveDate,
+}
+
+/// これまでに遊んだ全ゲームの集計
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct LifetimeStats {
+    pub played: u32,
+    pub won: u32,
+    /// 当てたゲームの試行回数の合計
+    pub won_attempts: u64,
+    /// 当てたゲームの試行回数ごとのゲーム数
+    pub histogram: BTreeMap<u32, u32>,
+}
+
+impl LifetimeStats {
+    pub fn win_rate(&self) -> f64 {
+        if self.played == 0 {
+            return 0.0;
+        }
+        self.won as f64 / self.played as f64
+    }
+
+    pub fn average_attempts(&self) -> Option<f64> {
+        (self.won > 0).then(|| self.won_attempts as f64 / self.won as f64)
+    }
+}
+
+/// 保存するファイルの中身
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct StatsFile {
+    pub version: u32,
+    /// 難易度の名前ごとのランキング
+    #[serde(default)]
+    pub leaderboards: BTreeMap<String, Vec<ScoreEntry>>,
+    #[serde(default)]
+    pub lifetime: LifetimeStats,
+    /// 前回ランキングに登録した名前
+    #[serde(default)]
+    pub last_name: String,
+}
+
+impl Default for StatsFile {
+    fn default() -> Self {
+        StatsFile {
+            version: STATS_VERSION,
+            leaderboards: BTreeMap::new(),
+            lifetime: LifetimeStats::default(),
+            last_name: String::new(),
+        }
+    }
+}
+
+/// ランキング入りして、名前の入力を待っている記録
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PendingScore {
+    pub board: String,
+    pub attempts: u32,
+    pub millis: u64,
+}
+
+pub struct StatsState {
+    pub file: StatsFile,
+    /// 成績画面で見ているランキング
+    pub board: String,
+    pub pending: Option<PendingScore>,
+    pub name_input: SharedString,
+    pub message: SharedString,
+    /// 同じゲームを二重に数えないように、最後に記録したゲームの開始時刻を覚えておく
+    recorded_game: Option<Instant>,
+    /// 保存先。テストでは一時ディレクトリを指す
+    path: Option<PathBuf>,
+    /// 読み込めなかったファイルを空の成績で上書きしないように、保存を止める
+    read_only: bool,
+}
+
+impl StatsState {
+    pub fn new() -> Self {
+        Self::open(Self::data_path())
+    }
+
+    fn open(path: Option<PathBuf>) -> Self {
+        let (file, message, read_only) = match Self::load(path.as_deref()) {
+            Ok(file) => (file.unwrap_or_default(), String::new(), false),
+            Err(err) => (
+                StatsFile::default(),
+                format!("成績の読み込みに失敗: {} (今回の成績は保存しない)", err),
+                true,
+            ),
+        };
+        StatsState {
+            name_input: file.last_name.clone().into(),
+            file,
+            board: Self::board_name(Difficulty::Normal),
+            pending: None,
+            message: message.into(),
+            recorded_game: None,
+            path,
+            read_only,
+        }
+    }
+
+    /// ランキングを分ける名前。自由範囲は範囲ごとに分ける
+    pub fn board_name(difficulty: Difficulty) -> String {
+        match difficulty {
+            Difficulty::Custom(range) => format!("Custom {}", range),
+            _ => difficulty.label().to_string(),
+        }
+    }
+
+    /// 成績画面で選べるランキング。プリセットのあとに記録のある自由範囲を並べる
+    pub fn boards(&self) -> Vec<String> {
+        let mut boards: Vec<String> = Difficulty::PRESETS
+            .iter()
+            .map(|difficulty| Self::board_name(*difficulty))
+            .collect();
+        for board in self.file.leaderboards.keys() {
+            if !boards.contains(board) {
+                boards.push(board.clone());
+            }
+        }
+        boards
+    }
+
+    pub fn leaderboard(&self, board: &str) -> &[ScoreEntry] {
+        self.file
+            .leaderboards
+            .get(board)
+            .map_or(&[], |entries| entries.as_slice())
+    }
+
+    /// 試行回数が少ないほど、同じなら時間が短いほど上
+    fn makes_table(&self, board: &str, attempts: u32, millis: u64) -> bool {
+        let entries = self.leaderboard(board);
+        entries.len() < LEADERBOARD_SIZE
+            || entries
+                .last()
+                .is_some_and(|last| (attempts, millis) < (last.attempts, last.millis))
+    }
+
+    /// 決着がついたゲームを集計に加える。ランキングに入るなら名前の入力を待つ。
+    /// 嘘や後出しのあるゲームはランキングには載せない
+    pub fn record(&mut self, game: &GameState) -> bool {
+        if game.status == GameStatus::Playing || self.recorded_game == Some(game.started_at) {
+            return false;
+        }
+        self.recorded_game = Some(game.started_at);
+
+        let won = game.status == GameStatus::Won;
+        let lifetime = &mut self.file.lifetime;
+        lifetime.played += 1;
+        if won {
+            lifetime.won += 1;
+            lifetime.won_attempts += game.attempts as u64;
+            *lifetime.histogram.entry(game.attempts).or_insert(0) += 1;
+        }
+
+        let fair = game.max_lies() == 0 && game.secret_kind == SecretKind::Random;
+        let board = Self::board_name(game.difficulty);
+        let millis = game.elapsed().as_millis() as u64;
+        if won && fair && self.makes_table(&board, game.attempts, millis) {
+            self.board = board.clone();
+            self.pending = Some(PendingScore {
+                board,
+                attempts: game.attempts,
+                millis,
+            });
+        }
+        self.save();
+        true
+    }
+
+    pub fn push_char(&mut self, c: &str, cx: &mut Context<Self>) {
+        if self.name_input.chars().count() + c.chars().count() <= NAME_MAX_CHARS {
+            self.name_input = format!("{}{}", self.name_input, c).into();
+        }
+        cx.notify();
+    }
+
+    pub fn pop_char(&mut self, cx: &mut Context<Self>) {
+        let mut chars = self.name_input.chars();
+        chars.next_back();
+        self.name_input = chars.as_str().to_string().into();
+        cx.notify();
+    }
+
+    /// 入力した名前でランキングに登録する
+    pub fn submit_name(&mut self, cx: &mut Context<Self>) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        let name = match self.name_input.trim() {
+            "" => "名無し".to_string(),
+            name => name.to_string(),
+        };
+        let entries = self.file.leaderboards.entry(pending.board).or_default();
+        entries.push(ScoreEntry {
+            name: name.clone(),
+            attempts: pending.attempts,
+            millis: pending.millis,
+            date: Local::now().date_naive(),
+        });
+        entries.sort_by_key(|entry| (entry.attempts, entry.millis));
+        entries.truncate(LEADERBOARD_SIZE);
+        self.file.last_name = name;
+        self.save();
+        cx.notify();
+    }
+
+    pub fn skip_name(&mut self, cx: &mut Context<Self>) {
+        self.pending = None;
+        cx.notify();
+    }
+
+    pub fn select_board(&mut self, board: String, cx: &mut Context<Self>) {
+        self.board = board;
+        cx.notify();
+    }
+
+    /// $XDG_DATA_HOME/guessing_game/stats.json (なければ ~/.local/share/guessing_game/stats.json)
+    pub fn data_path() -> Option<PathBuf> {
+        storage::xdg_path("XDG_DATA_HOME", ".local/share", "stats.json")
+    }
+
+    fn load(path: Option<&Path>) -> Result<Option<StatsFile>, String> {
+        let Some(path) = path else {
+            return Ok(None);
+        };
+        let Some(file) = storage::load_json::<StatsFile>(path)? else {
+            return Ok(None);
+        };
+        // 新しい版で保存したファイルは読み違えないように使わない
+        if file.version > STATS_VERSION {
+            return Err(format!("未対応のバージョン {}", file.version));
+        }
+        Ok(Some(file))
+    }
+
+    fn save(&mut self) {
+        if self.read_only {
+            return;
+        }
+        let Some(path) = &self.path else {
+            return;
+        };
+        self.file.version = STATS_VERSION;
+        if let Err(err) = storage::save_json(path, &self.file) {
+            self.message = format!("成績の保存に失敗: {}", err).into();
+        }
+    }
+}
+
+impl Default for StatsState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// プロセスごとに別の一時ディレクトリに置く保存先
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "guessing_game_stats_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("stats.json")
+    }
+
+    /// 1〜100で 80, 42 と当てたゲーム
+    fn won_game() -> GameState {
+        let mut game = GameState::new(Difficulty::Normal);
+        assert!(game.reload_with_secret(42));
+        assert!(game.submit("80").is_ok());
+        assert!(game.submit("42").is_ok());
+        assert_eq!(game.status, GameStatus::Won);
+        game
+    }
+
+    fn entry(attempts: u32, millis: u64) -> ScoreEntry {
+        ScoreEntry {
+            name: "test".to_string(),
+            attempts,
+            millis,
+            date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        }
+    }
+
+    #[test]
+    fn record_fills_the_histogram_and_the_difficulty_board() {
+        let path = temp_path("record");
+        let mut stats = StatsState::open(Some(path.clone()));
+        let game = won_game();
+        assert!(stats.record(&game));
+        assert!(!stats.record(&game));
+
+        let board = StatsState::board_name(Difficulty::Normal);
+        let lifetime = &stats.file.lifetime;
+        assert_eq!((lifetime.played, lifetime.won), (1, 1));
+        assert_eq!(lifetime.histogram, BTreeMap::from([(2, 1)]));
+        assert_eq!(
+            stats
+                .pending
+                .as_ref()
+                .map(|pending| (&pending.board, pending.attempts)),
+            Some((&board, 2))
+        );
+        assert_eq!(stats.board, board);
+
+        let saved: StatsFile = storage::load_json(&path).unwrap().unwrap();
+        assert_eq!(saved.lifetime, stats.file.lifetime);
+    }
+
+    #[test]
+    fn full_table_only_takes_strictly_better_scores() {
+        let mut stats = StatsState::open(None);
+        let board = StatsState::board_name(Difficulty::Normal);
+        let entries = vec![entry(5, 1000); LEADERBOARD_SIZE - 1];
+        stats.file.leaderboards.insert(board.clone(), entries);
+        assert!(stats.makes_table(&board, 99, u64::MAX));
+
+        stats
+            .file
+            .leaderboards
+            .get_mut(&board)
+            .unwrap()
+            .push(entry(5, 1000));
+        assert!(!stats.makes_table(&board, 99, 0));
+        assert!(!stats.makes_table(&board, 5, 1000));
+        assert!(stats.makes_table(&board, 5, 999));
+        assert!(stats.makes_table(&board, 4, u64::MAX));
+    }
+
+    #[test]
+    fn newer_file_version_is_rejected_and_never_overwritten() {
+        let path = temp_path("version");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let json = format!(r#"{{ "version": {} }}"#, STATS_VERSION + 1);
+        std::fs::write(&path, &json).unwrap();
+
+        assert!(StatsState::load(Some(&path)).is_err());
+        let mut stats = StatsState::open(Some(path.clone()));
+        assert!(stats.read_only);
+        assert!(!stats.message.is_empty());
+        assert_eq!(stats.file, StatsFile::default());
+
+        assert!(stats.record(&won_game()));
+        assert_eq!(stats.file.lifetime.played, 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), json);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::models::difficulty::NumberRange;
+use rand::Rng;
+use rand::rngs::StdRng;
+
+/// 残っている区間から次の予想を選ぶ作戦
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+    fn next_guess(&mut self, possible: NumberRange, rng: &mut StdRng) -> i64;
+}
+
+/// 真ん中を選ぶ
+pub struct Bisection;
+
+impl Strategy for Bisection {
+    fn name(&self) -> &'static str {
+        "bisection"
+    }
+
+    fn next_guess(&mut self, possible: NumberRange, _rng: &mut StdRng) -> i64 {
+        possible.midpoint()
+    }
+}
+
+/// 区間の中からランダムに選ぶ
+pub struct RandomInInterval;
+
+impl Strategy for RandomInInterval {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn next_guess(&mut self, possible: NumberRange, rng: &mut StdRng) -> i64 {
+        rng.random_range(possible.min..=possible.max)
+    }
+}
+
+/// 黄金比で区間を分ける点を選ぶ
+pub struct GoldenSection;
+
+impl Strategy for GoldenSection {
+    fn name(&self) -> &'static str {
+        "golden"
+    }
+
+    fn next_guess(&mut self, possible: NumberRange, _rng: &mut StdRng) -> i64 {
+        // 1 - 1/φ
+        const RATIO: f64 = 0.381_966_011_250_105;
+        possible.at_ratio(RATIO)
+    }
+}
+
+pub fn all_strategies() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(Bisection),
+        Box::new(RandomInInterval),
+        Box::new(GoldenSection),
+    ]
+}
+
+pub fn strategy_by_name(name: &str) -> Option<Box<dyn Strategy>> {
+    all_strategies()
+        .into_iter()
+        .find(|strategy| strategy.name() == name)
+}
@@ -1 +1,2 @@
 pub mod game;
+pub mod number_input;
@@ -1,38 +1,553 @@
 use crate::elements::{
-    guess::guess_element, input::input_element, message::message_element, reload::reload_element,
+    attempts::attempts_element,
+    bulls_cows::{bulls_cows_element, bulls_cows_setup_element},
+    coach::coach_element,
+    daily::daily_element,
+    feedback::{arrow_element, burst_element, effects_settings_element, shake_element},
+    guess::guess_element,
+    history::history_element,
+    hot_seat::{
+        hot_seat_handover_element, hot_seat_result_element, hot_seat_secret_element,
+        hot_seat_status_element,
+    },
+    keypad::keypad_element,
+    message::message_element,
+    mode_picker::mode_picker_element,
+    number_line::number_line_element,
+    race::{race_board_element, race_lobby_element},
+    range::range_element,
+    reload::reload_element,
+    reverse::reverse_element,
+    setup::setup_element,
+    slider::slider_element,
+    speed_run::speed_run_element,
+    stats::{high_score_banner_element, stats_element},
+    timer::timer_element,
     title::title_element,
 };
-use crate::models::game_state::GameState;
-use gpui::{Context, Entity, IntoElement, Render, Window};
+use crate::keymap::{
+    ClearInput, Decrement, FocusNext, FocusPrev, Increment, KEY_CONTEXT, Reload, Submit,
+};
+use crate::models::bulls_cows_state::BullsCowsState;
+use crate::models::daily::{DAILY_DIFFICULTY, DailyState};
+use crate::models::difficulty::Difficulty;
+use crate::models::effects_state::EffectsState;
+use crate::models::game_state::{GameState, GameStatus};
+use crate::models::hot_seat_state::{HotSeatPhase, HotSeatState};
+use crate::models::oracle::{Honest, Oracle};
+use crate::models::race_state::RaceState;
+use crate::models::reverse_state::{Reply, ReverseState};
+use crate::models::secret::SecretKind;
+use crate::models::setup_state::{GameKind, PlayMode, SetupState};
+use crate::models::speed_run_state::SpeedRunState;
+use crate::models::stats::StatsState;
+use crate::views::number_input::NumberInput;
+use gpui::{
+    AsyncApp, ClipboardItem, Context, Entity, FocusHandle, IntoElement, Render, Task, WeakEntity,
+    Window,
+};
 use gpui::{div, prelude::*, rgb};
+use std::time::{Duration, Instant};
+
+pub enum Scene {
+    Setup,
+    Game,
+    Reverse,
+    HotSeat,
+    Race,
+    BullsCows,
+    Stats,
+}
 
 pub struct GameView {
     // Game stateに対する参照
     game_state: Entity<GameState>,
+    setup_state: Entity<SetupState>,
+    reverse_state: Entity<ReverseState>,
+    hot_seat_state: Entity<HotSeatState>,
+    race_state: Entity<RaceState>,
+    daily_state: Entity<DailyState>,
+    bulls_cows_state: Entity<BullsCowsState>,
+    stats_state: Entity<StatsState>,
+    speed_run_state: Entity<SpeedRunState>,
+    effects_state: Entity<EffectsState>,
     focus_handle: gpui::FocusHandle,
+    // Tabで移動する入力欄、GUESS、Reload
+    input_focus: FocusHandle,
+    guess_focus: FocusHandle,
+    reload_focus: FocusHandle,
+    number_input: Entity<NumberInput>,
+    // 決着がついたゲームの振り返りを開いているか
+    coach_open: bool,
+    // 数字キーの下にスライダーを出すか
+    show_slider: bool,
+    // 時計を動かしているゲームの開始時刻。Reloadで変わったら時計を掛け直す
+    timer_game: Option<Instant>,
+    _timer_task: Option<Task<()>>,
+    scene: Scene,
 }
 
 impl GameView {
     pub fn new(view_cx: &mut Context<GameView>) -> Self {
-        let game_state = view_cx.new(|_model_cx| GameState::new());
+        let game_state = view_cx.new(|_model_cx| GameState::new(Difficulty::Normal));
+        let setup_state = view_cx.new(|_model_cx| SetupState::new());
+        let reverse_state = view_cx.new(|_model_cx| ReverseState::new(Difficulty::Normal.range()));
+        let hot_seat_state = view_cx.new(|_model_cx| HotSeatState::new());
+        let race_state = view_cx.new(|_model_cx| RaceState::new(Difficulty::Normal.range()));
+        let daily_state = view_cx.new(|_model_cx| DailyState::new());
+        let bulls_cows_state = view_cx.new(|_model_cx| BullsCowsState::default());
+        let stats_state = view_cx.new(|_model_cx| StatsState::new());
+        let speed_run_state = view_cx.new(|_model_cx| SpeedRunState::new());
+        let effects_state = view_cx.new(|_model_cx| EffectsState::new());
         let focus_handle = view_cx.focus_handle();
+        let input_focus = view_cx.focus_handle().tab_index(1).tab_stop(true);
+        let guess_focus = view_cx.focus_handle().tab_index(2).tab_stop(true);
+        let reload_focus = view_cx.focus_handle().tab_index(3).tab_stop(true);
+        let number_input = view_cx
+            .new(|input_cx| NumberInput::new(game_state.clone(), input_focus.clone(), input_cx));
+        // 予想に反応して演出を出し、決着がついたらデイリーの結果と成績を記録する。
+        // 新しいゲームなら時計を動かす
+        view_cx
+            .observe(&game_state, |view, game_state, cx| {
+                view.react_effects(game_state.clone(), cx);
+                view.record_daily(game_state.clone(), cx);
+                view.record_stats(game_state.clone(), cx);
+                view.record_speed_run(game_state.clone(), cx);
+                view.sync_timer(game_state, cx);
+            })
+            .detach();
         GameView {
             game_state,
+            setup_state,
+            reverse_state,
+            hot_seat_state,
+            race_state,
+            daily_state,
+            bulls_cows_state,
+            stats_state,
+            speed_run_state,
+            effects_state,
             focus_handle,
+            input_focus,
+            guess_focus,
+            reload_focus,
+            number_input,
+            coach_open: false,
+            show_slider: false,
+            timer_game: None,
+            _timer_task: None,
+            scene: Scene::Setup,
         }
     }
-}
 
-impl Render for GameView {
-    fn render(
+    pub fn start_game(&mut self, difficulty: Difficulty, cx: &mut Context<GameView>) {
+        let setup = self.setup_state.read(cx);
+        let (limit_attempts, mode, rounds) = (setup.limit_attempts, setup.mode, setup.rounds);
+        let speed_run_rounds = setup.speed_run_rounds;
+        let time_limit = match mode {
+            PlayMode::Solo | PlayMode::SpeedRun => setup.time_limit(),
+            _ => None,
+        };
+        // 嘘をつくのも答えを後出しするのも、ひとりで当てるときだけ
+        let (oracle, secret_kind) = match mode {
+            PlayMode::Solo => (setup.liar.build(setup.lies), setup.secret_kind),
+            _ => (Box::new(Honest) as Box<dyn Oracle>, SecretKind::Random),
+        };
+        self.daily_state
+            .update(cx, |daily, model_cx| daily.stop(model_cx));
+        self.speed_run_state
+            .update(cx, |speed_run, model_cx| speed_run.stop(model_cx));
+        match mode {
+            PlayMode::Reverse => {
+                self.reverse_state.update(cx, |reverse, model_cx| {
+                    reverse.start(difficulty.range(), model_cx);
+                });
+                self.scene = Scene::Reverse;
+            }
+            PlayMode::Race => {
+                self.race_state.update(cx, |race, model_cx| {
+                    race.open_lobby(difficulty.range(), model_cx);
+                });
+                self.scene = Scene::Race;
+            }
+            PlayMode::Solo | PlayMode::HotSeat | PlayMode::SpeedRun => {
+                self.game_state.update(cx, |game, model_cx| {
+                    game.time_limit = time_limit;
+                    game.set_difficulty(difficulty, limit_attempts);
+                    game.set_oracle(oracle);
+                    game.set_secret_kind(secret_kind);
+                    model_cx.notify();
+                });
+                if mode == PlayMode::HotSeat {
+                    self.hot_seat_state.update(cx, |hot_seat, model_cx| {
+                        hot_seat.start(rounds, model_cx);
+                    });
+                    self.scene = Scene::HotSeat;
+                } else {
+                    if mode == PlayMode::SpeedRun {
+                        self.speed_run_state.update(cx, |speed_run, model_cx| {
+                            speed_run.start(speed_run_rounds, model_cx);
+                        });
+                    }
+                    self.scene = Scene::Game;
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    pub fn start_bulls_cows(&mut self, cx: &mut Context<GameView>) {
+        let settings = self.setup_state.read(cx).bulls_cows;
+        self.daily_state
+            .update(cx, |daily, model_cx| daily.stop(model_cx));
+        self.speed_run_state
+            .update(cx, |speed_run, model_cx| speed_run.stop(model_cx));
+        self.bulls_cows_state.update(cx, |state, model_cx| {
+            state.start(settings, model_cx);
+        });
+        self.scene = Scene::BullsCows;
+        cx.notify();
+    }
+
+    /// 日付から決まる答えで、回数制限ありの1回だけのゲームを始める
+    pub fn start_daily(&mut self, cx: &mut Context<GameView>) {
+        let secret = self
+            .daily_state
+            .update(cx, |daily, model_cx| daily.start(model_cx));
+        let Some(secret) = secret else {
+            self.setup_state.update(cx, |setup, model_cx| {
+                setup.message = "今日のチャレンジはもう遊んだよ。また明日".into();
+                model_cx.notify();
+            });
+            return;
+        };
+        self.speed_run_state
+            .update(cx, |speed_run, model_cx| speed_run.stop(model_cx));
+        self.game_state.update(cx, |game, model_cx| {
+            game.time_limit = None;
+            game.set_difficulty(DAILY_DIFFICULTY, true);
+            game.set_oracle(Box::new(Honest));
+            game.set_secret_kind(SecretKind::Random);
+            game.reload_with_secret(secret);
+            model_cx.notify();
+        });
+        self.scene = Scene::Game;
+        cx.notify();
+    }
+
+    fn record_daily(&mut self, game_state: Entity<GameState>, cx: &mut Context<GameView>) {
+        let share_text = self.daily_state.update(cx, |daily, model_cx| {
+            let share_text = daily.finish(game_state.read(model_cx));
+            if share_text.is_some() {
+                model_cx.notify();
+            }
+            share_text
+        });
+        if let Some(share_text) = share_text {
+            cx.write_to_clipboard(ClipboardItem::new_string(share_text));
+            self.daily_state.update(cx, |daily, model_cx| {
+                daily.message = "結果をクリップボードにコピーしました".into();
+                model_cx.notify();
+            });
+        }
+    }
+
+    fn record_stats(&mut self, game_state: Entity<GameState>, cx: &mut Context<GameView>) {
+        // 対戦の勝ち負けは自分の成績に入れない
+        if !matches!(self.scene, Scene::Game) {
+            return;
+        }
+        self.stats_state.update(cx, |stats, model_cx| {
+            if stats.record(game_state.read(model_cx)) {
+                model_cx.notify();
+            }
+        });
+    }
+
+    fn react_effects(&mut self, game_state: Entity<GameState>, cx: &mut Context<GameView>) {
+        self.effects_state.update(cx, |effects, model_cx| {
+            if effects.react(game_state.read(model_cx)) {
+                model_cx.notify();
+            }
+        });
+    }
+
+    fn record_speed_run(&mut self, game_state: Entity<GameState>, cx: &mut Context<GameView>) {
+        self.speed_run_state.update(cx, |speed_run, model_cx| {
+            if speed_run.finish_round(game_state.read(model_cx)) {
+                model_cx.notify();
+            }
+        });
+    }
+
+    /// 新しいゲームが始まったら時計を掛け直す。前の時計は落として止める
+    fn sync_timer(&mut self, game_state: Entity<GameState>, cx: &mut Context<GameView>) {
+        let game = game_state.read(cx);
+        if game.status != GameStatus::Playing || self.timer_game == Some(game.started_at) {
+            return;
+        }
+        self.timer_game = Some(game.started_at);
+        self._timer_task = Some(cx.spawn(|we: WeakEntity<GameView>, cx_ref: &mut AsyncApp| {
+            let mut cx = cx_ref.clone();
+            async move {
+                loop {
+                    cx.background_executor()
+                        .timer(Duration::from_millis(100))
+                        .await;
+                    // 画面がなくなっていたら止める
+                    let should_stop = we
+                        .update(&mut cx, |view, view_cx| view.tick_timer(view_cx))
+                        .unwrap_or(true);
+                    if should_stop {
+                        break;
+                    }
+                }
+            }
+        }));
+    }
+
+    /// 時間切れを確かめて時計を描き直す。もう動かす必要がなければ true
+    fn tick_timer(&mut self, cx: &mut Context<GameView>) -> bool {
+        self.game_state.update(cx, |game, model_cx| {
+            if game.check_timeout() {
+                model_cx.notify();
+            }
+        });
+        cx.notify();
+        // スピードランはラウンドの合間も通しの時計を進める
+        let playing = self.game_state.read(cx).status == GameStatus::Playing
+            || self.speed_run_state.read(cx).is_running();
+        if !playing || !matches!(self.scene, Scene::Game | Scene::HotSeat) {
+            self._timer_task = None;
+            self.timer_game = None;
+            return true;
+        }
+        false
+    }
+
+    pub fn open_stats(&mut self, cx: &mut Context<GameView>) {
+        self.daily_state
+            .update(cx, |daily, model_cx| daily.stop(model_cx));
+        self.speed_run_state
+            .update(cx, |speed_run, model_cx| speed_run.stop(model_cx));
+        self.scene = Scene::Stats;
+        cx.notify();
+    }
+
+    /// 出題する人が入力した数を答えにして、当てる人に渡す
+    pub fn confirm_hot_seat_secret(&mut self, cx: &mut Context<GameView>) {
+        let secret = self
+            .hot_seat_state
+            .update(cx, |hot_seat, _model_cx| hot_seat.take_secret());
+        let accepted = secret.is_some_and(|secret| {
+            self.game_state.update(cx, |game, model_cx| {
+                let accepted = game.reload_with_secret(secret);
+                model_cx.notify();
+                accepted
+            })
+        });
+        let range = self.game_state.read(cx).range();
+        self.hot_seat_state.update(cx, |hot_seat, model_cx| {
+            if accepted {
+                hot_seat.set_phase(HotSeatPhase::Handover, model_cx);
+            } else {
+                hot_seat.message = format!("{} の数を入力してね", range).into();
+                model_cx.notify();
+            }
+        });
+    }
+
+    pub fn finish_hot_seat_turn(&mut self, cx: &mut Context<GameView>) {
+        let game = self.game_state.read(cx);
+        if game.status == GameStatus::Playing {
+            return;
+        }
+        let (attempts, won) = (game.attempts, game.status == GameStatus::Won);
+        self.hot_seat_state.update(cx, |hot_seat, model_cx| {
+            hot_seat.finish_turn(attempts, won, model_cx);
+        });
+    }
+
+    pub fn start_custom_game(&mut self, cx: &mut Context<GameView>) {
+        let range = self
+            .setup_state
+            .update(cx, |setup, model_cx| setup.custom_range(model_cx));
+        if let Some(range) = range {
+            self.start_game(Difficulty::Custom(range), cx);
+        }
+    }
+
+    pub fn open_setup(&mut self, cx: &mut Context<GameView>) {
+        self.daily_state
+            .update(cx, |daily, model_cx| daily.stop(model_cx));
+        self.speed_run_state
+            .update(cx, |speed_run, model_cx| speed_run.stop(model_cx));
+        self.scene = Scene::Setup;
+        cx.notify();
+    }
+
+    fn setup_render(
         &mut self,
         _window: &mut Window,
         game_cx: &mut Context<GameView>,
+    ) -> impl IntoElement {
+        let setup_ticket = self.setup_state.clone();
+        let game_kind = setup_ticket.read(game_cx).game_kind;
+        let content = match game_kind {
+            GameKind::Number => setup_element(setup_ticket.clone(), game_cx).into_any_element(),
+            GameKind::BullsAndCows => {
+                bulls_cows_setup_element(setup_ticket.clone(), game_cx).into_any_element()
+            }
+        };
+        div()
+            .id("root")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .bg(rgb(0x1e1e1e))
+            .gap_4()
+            .on_key_down({
+                let view_ticket = game_cx.entity().clone();
+                let setup_ticket = setup_ticket.clone();
+                move |event, _window, cx| {
+                    let key = event.keystroke.key.as_str();
+                    if key == "enter" {
+                        view_ticket.update(cx, |view, view_cx| match game_kind {
+                            GameKind::Number => view.start_custom_game(view_cx),
+                            GameKind::BullsAndCows => view.start_bulls_cows(view_cx),
+                        });
+                        return;
+                    }
+                    if game_kind != GameKind::Number {
+                        return;
+                    }
+                    setup_ticket.update(cx, |setup, model_cx| {
+                        if ("0"..="9").contains(&key) || key == "-" {
+                            setup.push_digit(key, model_cx);
+                        } else if key == "backspace" {
+                            setup.pop_digit(model_cx);
+                        } else if key == "tab" {
+                            setup.toggle_field(model_cx);
+                        }
+                    })
+                }
+            })
+            .child(mode_picker_element(setup_ticket.clone(), game_cx))
+            .child(content)
+            .child(effects_settings_element(
+                self.effects_state.clone(),
+                game_cx,
+            ))
+    }
+
+    pub fn open_coach(&mut self, cx: &mut Context<GameView>) {
+        self.coach_open = true;
+        cx.notify();
+    }
+
+    pub fn close_coach(&mut self, cx: &mut Context<GameView>) {
+        self.coach_open = false;
+        cx.notify();
+    }
+
+    pub fn toggle_slider(&mut self, cx: &mut Context<GameView>) {
+        self.show_slider = !self.show_slider;
+        cx.notify();
+    }
+
+    /// 対戦中とデイリーは答えを引き直せないようにする。
+    /// スピードランはラウンドが終わってから次へ進むのに使う
+    fn can_reload(&self, cx: &Context<GameView>) -> bool {
+        let speed_run = self.speed_run_state.read(cx);
+        let round_over = self.game_state.read(cx).status != GameStatus::Playing;
+        !matches!(self.scene, Scene::HotSeat)
+            && !self.daily_state.read(cx).active
+            && (!speed_run.active || (round_over && !speed_run.is_complete()))
+    }
+
+    fn submit(&mut self, _: &Submit, window: &mut Window, cx: &mut Context<GameView>) {
+        // Reloadにフォーカスがあるときはそちらを押したことにする
+        if self.reload_focus.is_focused(window) {
+            self.reload(&Reload, window, cx);
+            return;
+        }
+        self.game_state
+            .update(cx, |game, model_cx| game.sub_guess(model_cx));
+    }
+
+    fn reload(&mut self, _: &Reload, _window: &mut Window, cx: &mut Context<GameView>) {
+        if !self.can_reload(cx) {
+            return;
+        }
+        self.game_state.update(cx, |game, model_cx| {
+            game.reload();
+            model_cx.notify();
+        });
+    }
+
+    fn clear_input(&mut self, _: &ClearInput, _window: &mut Window, cx: &mut Context<GameView>) {
+        self.game_state.update(cx, |game, model_cx| {
+            game.clear_input();
+            model_cx.notify();
+        });
+    }
+
+    fn increment(&mut self, _: &Increment, _window: &mut Window, cx: &mut Context<GameView>) {
+        self.game_state.update(cx, |game, model_cx| {
+            game.step_input(1);
+            model_cx.notify();
+        });
+    }
+
+    fn decrement(&mut self, _: &Decrement, _window: &mut Window, cx: &mut Context<GameView>) {
+        self.game_state.update(cx, |game, model_cx| {
+            game.step_input(-1);
+            model_cx.notify();
+        });
+    }
+
+    fn focus_next(&mut self, _: &FocusNext, window: &mut Window, _cx: &mut Context<GameView>) {
+        window.focus_next();
+    }
+
+    fn focus_prev(&mut self, _: &FocusPrev, window: &mut Window, _cx: &mut Context<GameView>) {
+        window.focus_prev();
+    }
+
+    fn game_render(
+        &mut self,
+        window: &mut Window,
+        game_cx: &mut Context<GameView>,
     ) -> impl IntoElement {
         let state_ticket = self.game_state.clone();
+        // 次のゲームが始まったら振り返りは閉じる
+        if state_ticket.read(game_cx).status == GameStatus::Playing {
+            self.coach_open = false;
+        }
+        if self.coach_open {
+            return self.coach_render(game_cx).into_any_element();
+        }
+        let can_reload = self.can_reload(game_cx);
+        // どこにもフォーカスがなければすぐ打てるように入力欄へ
+        if window.focused(game_cx).is_none() {
+            window.focus(&self.input_focus);
+        }
+        let daily = self.daily_state.read(game_cx).active;
         div()
             .id("root")
+            .key_context(KEY_CONTEXT)
             .track_focus(&self.focus_handle)
+            .on_action(game_cx.listener(Self::submit))
+            .on_action(game_cx.listener(Self::reload))
+            .on_action(game_cx.listener(Self::clear_input))
+            .on_action(game_cx.listener(Self::increment))
+            .on_action(game_cx.listener(Self::decrement))
+            .on_action(game_cx.listener(Self::focus_next))
+            .on_action(game_cx.listener(Self::focus_prev))
+            .relative()
             .flex()
             .flex_col()
             .size_full()
@@ -42,31 +557,360 @@ impl Render for GameView {
             .gap_4()
             .on_key_down({
                 let state_ticket = state_ticket.clone();
-                move |event, _window, cx| {
+                let input_focus = self.input_focus.clone();
+                move |event, window, cx| {
                     let key = event.keystroke.key.as_str();
                     println!("Key pressed: {}", key);
+                    // 入力欄にフォーカスがあるときは入力欄が受け取る
+                    if input_focus.is_focused(window) {
+                        return;
+                    }
                     state_ticket.update(cx, |game, model_cx| {
-                        if ("0"..="9").contains(&key) && game.current_input.len() < 2 {
-                            game.current_input = format!("{}{}", game.current_input, key).into();
-                        } else if key == "backspace" {
-                            let mut chars = game.current_input.chars();
-                            chars.next_back();
-                            game.current_input = chars.as_str().to_string().into();
+                        if key == "backspace" {
+                            game.pop_digit();
+                        } else {
+                            game.push_digit(key);
                         }
                         model_cx.notify();
                     })
                 }
             })
-            .child(title_element())
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap_4()
+                    .child(title_element())
+                    .child(timer_element(
+                        state_ticket.clone(),
+                        self.speed_run_state.clone(),
+                        game_cx,
+                    )),
+            )
+            .when(self.speed_run_state.read(game_cx).active, |this| {
+                this.child(speed_run_element(self.speed_run_state.clone(), game_cx))
+            })
+            .when(matches!(self.scene, Scene::HotSeat), |this| {
+                this.child(hot_seat_status_element(
+                    self.hot_seat_state.clone(),
+                    state_ticket.clone(),
+                    game_cx,
+                ))
+            })
+            .when(daily, |this| {
+                this.child(daily_element(self.daily_state.clone(), game_cx))
+            })
+            .child(range_element(state_ticket.clone(), game_cx))
+            .child(attempts_element(state_ticket.clone(), game_cx))
             .child(
                 div()
                     .flex()
                     .flex_row()
                     .gap_2()
-                    .child(input_element(state_ticket.clone(), game_cx))
-                    .child(guess_element(state_ticket.clone()))
-                    .child(reload_element(state_ticket.clone())),
+                    .child(shake_element(
+                        self.number_input.clone().into_any_element(),
+                        self.effects_state.clone(),
+                        game_cx,
+                    ))
+                    .child(guess_element(
+                        state_ticket.clone(),
+                        &self.guess_focus,
+                        window,
+                    ))
+                    .when(can_reload, |this| {
+                        this.child(reload_element(
+                            state_ticket.clone(),
+                            &self.reload_focus,
+                            window,
+                        ))
+                    })
+                    .child(history_element(state_ticket.clone(), game_cx)),
             )
-            .child(message_element(state_ticket.clone(), game_cx))
+            .child(number_line_element(state_ticket.clone(), game_cx))
+            .child(keypad_element(
+                state_ticket.clone(),
+                self.show_slider,
+                game_cx,
+            ))
+            .when(self.show_slider, |this| {
+                this.child(slider_element(state_ticket.clone(), game_cx))
+            })
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap_2()
+                    .child(arrow_element(self.effects_state.clone(), game_cx))
+                    .child(message_element(state_ticket.clone(), game_cx)),
+            )
+            .when(self.stats_state.read(game_cx).pending.is_some(), |this| {
+                this.child(high_score_banner_element(game_cx))
+            })
+            .child(burst_element(self.effects_state.clone(), game_cx))
+            .into_any_element()
+    }
+
+    fn coach_render(&mut self, game_cx: &mut Context<GameView>) -> impl IntoElement {
+        div()
+            .id("root")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .bg(rgb(0x1e1e1e))
+            .gap_4()
+            .on_key_down({
+                let view_ticket = game_cx.entity().clone();
+                move |event, _window, cx| {
+                    let key = event.keystroke.key.as_str();
+                    if key == "escape" {
+                        view_ticket.update(cx, |view, view_cx| view.close_coach(view_cx));
+                    }
+                }
+            })
+            .child(title_element())
+            .child(coach_element(self.game_state.clone(), game_cx))
+    }
+
+    fn reverse_render(
+        &mut self,
+        _window: &mut Window,
+        game_cx: &mut Context<GameView>,
+    ) -> impl IntoElement {
+        let reverse_ticket = self.reverse_state.clone();
+        div()
+            .id("root")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .bg(rgb(0x1e1e1e))
+            .gap_4()
+            .on_key_down({
+                let reverse_ticket = reverse_ticket.clone();
+                move |event, _window, cx| {
+                    let key = event.keystroke.key.as_str();
+                    let reply = match key {
+                        "up" => Reply::Higher,
+                        "down" => Reply::Lower,
+                        "enter" => Reply::Correct,
+                        _ => return,
+                    };
+                    reverse_ticket.update(cx, |reverse, model_cx| {
+                        reverse.answer(reply, model_cx);
+                    })
+                }
+            })
+            .child(title_element())
+            .child(reverse_element(reverse_ticket.clone(), game_cx))
+    }
+
+    fn hot_seat_render(
+        &mut self,
+        window: &mut Window,
+        game_cx: &mut Context<GameView>,
+    ) -> impl IntoElement {
+        let hot_seat_ticket = self.hot_seat_state.clone();
+        let state_ticket = self.game_state.clone();
+        let phase = hot_seat_ticket.read(game_cx).phase;
+        if phase == HotSeatPhase::Guessing {
+            return self.game_render(window, game_cx).into_any_element();
+        }
+        let content = match phase {
+            HotSeatPhase::SetSecret => {
+                hot_seat_secret_element(hot_seat_ticket.clone(), state_ticket.clone(), game_cx)
+                    .into_any_element()
+            }
+            HotSeatPhase::Handover => {
+                hot_seat_handover_element(hot_seat_ticket.clone(), game_cx).into_any_element()
+            }
+            _ => hot_seat_result_element(hot_seat_ticket.clone(), game_cx).into_any_element(),
+        };
+        div()
+            .id("root")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .bg(rgb(0x1e1e1e))
+            .gap_4()
+            .on_key_down({
+                let view_ticket = game_cx.entity().clone();
+                move |event, _window, cx| {
+                    let key = event.keystroke.key.as_str();
+                    if phase != HotSeatPhase::SetSecret {
+                        return;
+                    }
+                    if key == "enter" {
+                        view_ticket
+                            .update(cx, |view, view_cx| view.confirm_hot_seat_secret(view_cx));
+                        return;
+                    }
+                    let range = state_ticket.read(cx).range();
+                    hot_seat_ticket.update(cx, |hot_seat, model_cx| {
+                        if ("0"..="9").contains(&key) || key == "-" {
+                            hot_seat.push_digit(key, range, model_cx);
+                        } else if key == "backspace" {
+                            hot_seat.pop_digit(model_cx);
+                        }
+                    })
+                }
+            })
+            .child(title_element())
+            .child(content)
+            .into_any_element()
+    }
+
+    fn race_render(
+        &mut self,
+        _window: &mut Window,
+        game_cx: &mut Context<GameView>,
+    ) -> impl IntoElement {
+        let race_ticket = self.race_state.clone();
+        let connected = race_ticket.read(game_cx).connection.is_some();
+        let content = if connected {
+            race_board_element(race_ticket.clone(), game_cx).into_any_element()
+        } else {
+            race_lobby_element(race_ticket.clone(), game_cx).into_any_element()
+        };
+        div()
+            .id("root")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .bg(rgb(0x1e1e1e))
+            .gap_4()
+            .on_key_down({
+                let race_ticket = race_ticket.clone();
+                move |event, _window, cx| {
+                    let key = event.keystroke.key.as_str();
+                    race_ticket.update(cx, |race, model_cx| {
+                        if connected {
+                            if ("0"..="9").contains(&key) || key == "-" {
+                                race.push_digit(key, model_cx);
+                            } else if key == "backspace" {
+                                race.pop_digit(model_cx);
+                            } else if key == "enter" {
+                                race.submit_guess(model_cx);
+                            }
+                        } else if key == "backspace" {
+                            race.pop_char(model_cx);
+                        } else if key == "tab" {
+                            race.toggle_field(model_cx);
+                        } else if key == "enter" {
+                            race.join(model_cx);
+                        } else if let Some(c) = &event.keystroke.key_char {
+                            race.push_char(c, model_cx);
+                        }
+                    })
+                }
+            })
+            .child(title_element())
+            .child(content)
+    }
+
+    fn bulls_cows_render(
+        &mut self,
+        _window: &mut Window,
+        game_cx: &mut Context<GameView>,
+    ) -> impl IntoElement {
+        let bulls_cows_ticket = self.bulls_cows_state.clone();
+        div()
+            .id("root")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .bg(rgb(0x1e1e1e))
+            .gap_4()
+            .on_key_down({
+                let bulls_cows_ticket = bulls_cows_ticket.clone();
+                move |event, _window, cx| {
+                    let key = event.keystroke.key.as_str();
+                    bulls_cows_ticket.update(cx, |state, model_cx| {
+                        if ("0"..="9").contains(&key) {
+                            state.push_digit(key, model_cx);
+                        } else if key == "backspace" {
+                            state.pop_digit(model_cx);
+                        } else if key == "enter" {
+                            state.sub_guess(model_cx);
+                        }
+                    })
+                }
+            })
+            .child(title_element())
+            .child(bulls_cows_element(bulls_cows_ticket.clone(), game_cx))
+    }
+
+    fn stats_render(
+        &mut self,
+        _window: &mut Window,
+        game_cx: &mut Context<GameView>,
+    ) -> impl IntoElement {
+        let stats_ticket = self.stats_state.clone();
+        div()
+            .id("root")
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .bg(rgb(0x1e1e1e))
+            .gap_4()
+            .on_key_down({
+                let view_ticket = game_cx.entity().clone();
+                let stats_ticket = stats_ticket.clone();
+                move |event, _window, cx| {
+                    let key = event.keystroke.key.as_str();
+                    // 名前の入力中でなければ Escape で戻る
+                    if stats_ticket.read(cx).pending.is_none() {
+                        if key == "escape" {
+                            view_ticket.update(cx, |view, view_cx| view.open_setup(view_cx));
+                        }
+                        return;
+                    }
+                    stats_ticket.update(cx, |stats, model_cx| {
+                        if key == "enter" {
+                            stats.submit_name(model_cx);
+                        } else if key == "escape" {
+                            stats.skip_name(model_cx);
+                        } else if key == "backspace" {
+                            stats.pop_char(model_cx);
+                        } else if let Some(c) = &event.keystroke.key_char {
+                            stats.push_char(c, model_cx);
+                        }
+                    })
+                }
+            })
+            .child(title_element())
+            .child(stats_element(stats_ticket.clone(), game_cx))
+    }
+}
+
+impl Render for GameView {
+    fn render(&mut self, window: &mut Window, game_cx: &mut Context<GameView>) -> impl IntoElement {
+        match self.scene {
+            Scene::Setup => self.setup_render(window, game_cx).into_any_element(),
+            Scene::Game => self.game_render(window, game_cx).into_any_element(),
+            Scene::Reverse => self.reverse_render(window, game_cx).into_any_element(),
+            Scene::HotSeat => self.hot_seat_render(window, game_cx).into_any_element(),
+            Scene::Race => self.race_render(window, game_cx).into_any_element(),
+            Scene::BullsCows => self.bulls_cows_render(window, game_cx).into_any_element(),
+            Scene::Stats => self.stats_render(window, game_cx).into_any_element(),
+        }
     }
 }
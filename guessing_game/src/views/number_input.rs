@@ -0,0 +1,650 @@
+use crate::keymap::{
+    Backspace, Copy, Cut, Delete, End, Home, INPUT_KEY_CONTEXT, Left, Paste, Right, SelectAll,
+    SelectLeft, SelectRight,
+};
+use crate::models::game_state::GameState;
+use crate::models::number_format::normalize_number;
+use gpui::{
+    App, AsyncApp, Bounds, ClipboardItem, Context, CursorStyle, Element, ElementId,
+    ElementInputHandler, Entity, EntityInputHandler, FocusHandle, Focusable, GlobalElementId,
+    LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, Pixels, Point,
+    ShapedLine, SharedString, Style, Task, TextRun, UTF16Selection, UnderlineStyle, WeakEntity,
+    Window, div, fill, point, prelude::*, px, relative, rgb, rgba, size,
+};
+use std::ops::Range;
+use std::time::Duration;
+
+/// GameState の current_input を編集する1行の数値入力欄。
+/// キャレットや選択範囲、IMEの変換中の範囲はここで持つ
+pub struct NumberInput {
+    state_ticket: Entity<GameState>,
+    focus_handle: FocusHandle,
+    selected_range: Range<usize>,
+    selection_reversed: bool,
+    marked_range: Option<Range<usize>>,
+    // 自分で最後に書き込んだ内容。違っていたら外で書き換えられた
+    last_content: SharedString,
+    last_layout: Option<ShapedLine>,
+    last_bounds: Option<Bounds<Pixels>>,
+    is_selecting: bool,
+    cursor_visible: bool,
+    _blink_task: Task<()>,
+}
+
+impl NumberInput {
+    pub fn new(
+        state_ticket: Entity<GameState>,
+        focus_handle: FocusHandle,
+        cx: &mut Context<NumberInput>,
+    ) -> Self {
+        // Reloadや上下キーで書き換わったらキャレットを末尾に置き直す
+        cx.observe(&state_ticket, |input, state_ticket, cx| {
+            let content = state_ticket.read(cx).current_input.clone();
+            if content != input.last_content {
+                input.last_content = content.clone();
+                input.selected_range = content.len()..content.len();
+                input.selection_reversed = false;
+                input.marked_range = None;
+            }
+            cx.notify();
+        })
+        .detach();
+
+        let _blink_task = cx.spawn(|we: WeakEntity<NumberInput>, cx_ref: &mut AsyncApp| {
+            let mut cx = cx_ref.clone();
+            async move {
+                loop {
+                    cx.background_executor()
+                        .timer(Duration::from_millis(500))
+                        .await;
+                    let result = we.update(&mut cx, |input, input_cx| {
+                        input.cursor_visible = !input.cursor_visible;
+                        input_cx.notify();
+                    });
+                    if result.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let last_content = state_ticket.read(cx).current_input.clone();
+        NumberInput {
+            state_ticket,
+            focus_handle,
+            selected_range: last_content.len()..last_content.len(),
+            selection_reversed: false,
+            marked_range: None,
+            last_content,
+            last_layout: None,
+            last_bounds: None,
+            is_selecting: false,
+            cursor_visible: true,
+            _blink_task,
+        }
+    }
+
+    fn content(&self, cx: &App) -> SharedString {
+        self.state_ticket.read(cx).current_input.clone()
+    }
+
+    fn set_content(&mut self, content: String, cx: &mut Context<Self>) {
+        let content: SharedString = content.into();
+        self.last_content = content.clone();
+        self.state_ticket.update(cx, |game, model_cx| {
+            game.current_input = content;
+            model_cx.notify();
+        });
+    }
+
+    fn left(&mut self, _: &Left, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.move_to(self.previous_boundary(self.cursor_offset(), cx), cx);
+        } else {
+            self.move_to(self.selected_range.start, cx)
+        }
+    }
+
+    fn right(&mut self, _: &Right, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.move_to(self.next_boundary(self.selected_range.end, cx), cx);
+        } else {
+            self.move_to(self.selected_range.end, cx)
+        }
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.previous_boundary(self.cursor_offset(), cx), cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.next_boundary(self.cursor_offset(), cx), cx);
+    }
+
+    fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(0, cx);
+        self.select_to(self.content(cx).len(), cx)
+    }
+
+    fn home(&mut self, _: &Home, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(0, cx);
+    }
+
+    fn end(&mut self, _: &End, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.content(cx).len(), cx);
+    }
+
+    fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.previous_boundary(self.cursor_offset(), cx), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    fn delete(&mut self, _: &Delete, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.next_boundary(self.cursor_offset(), cx), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
+        // 数字だけ取り出すのは replace_text_in_range でやる
+        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            self.replace_text_in_range(None, &text, window, cx);
+        }
+    }
+
+    fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.selected_range.is_empty() {
+            let content = self.content(cx);
+            cx.write_to_clipboard(ClipboardItem::new_string(
+                content[self.selected_range.clone()].to_string(),
+            ));
+        }
+    }
+
+    fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.selected_range.is_empty() {
+            self.copy(&Copy, window, cx);
+            self.replace_text_in_range(None, "", window, cx)
+        }
+    }
+
+    fn on_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.is_selecting = true;
+        let index = self.index_for_mouse_position(event.position, cx);
+        if event.modifiers.shift {
+            self.select_to(index, cx);
+        } else {
+            self.move_to(index, cx)
+        }
+    }
+
+    fn on_mouse_up(&mut self, _: &MouseUpEvent, _window: &mut Window, _: &mut Context<Self>) {
+        self.is_selecting = false;
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseMoveEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if self.is_selecting {
+            let index = self.index_for_mouse_position(event.position, cx);
+            self.select_to(index, cx);
+        }
+    }
+
+    fn move_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.selected_range = offset..offset;
+        self.cursor_visible = true;
+        cx.notify()
+    }
+
+    fn cursor_offset(&self) -> usize {
+        if self.selection_reversed {
+            self.selected_range.start
+        } else {
+            self.selected_range.end
+        }
+    }
+
+    fn index_for_mouse_position(&self, position: Point<Pixels>, cx: &App) -> usize {
+        let content = self.content(cx);
+        if content.is_empty() {
+            return 0;
+        }
+        let (Some(bounds), Some(line)) = (self.last_bounds.as_ref(), self.last_layout.as_ref())
+        else {
+            return 0;
+        };
+        if position.y < bounds.top() {
+            return 0;
+        }
+        if position.y > bounds.bottom() {
+            return content.len();
+        }
+        line.closest_index_for_x(position.x - bounds.left())
+    }
+
+    fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        if self.selection_reversed {
+            self.selected_range.start = offset
+        } else {
+            self.selected_range.end = offset
+        };
+        if self.selected_range.end < self.selected_range.start {
+            self.selection_reversed = !self.selection_reversed;
+            self.selected_range = self.selected_range.end..self.selected_range.start;
+        }
+        cx.notify()
+    }
+
+    fn offset_from_utf16(content: &str, offset: usize) -> usize {
+        let mut utf8_offset = 0;
+        let mut utf16_count = 0;
+        for ch in content.chars() {
+            if utf16_count >= offset {
+                break;
+            }
+            utf16_count += ch.len_utf16();
+            utf8_offset += ch.len_utf8();
+        }
+        utf8_offset
+    }
+
+    fn offset_to_utf16(content: &str, offset: usize) -> usize {
+        let mut utf16_offset = 0;
+        let mut utf8_count = 0;
+        for ch in content.chars() {
+            if utf8_count >= offset {
+                break;
+            }
+            utf8_count += ch.len_utf8();
+            utf16_offset += ch.len_utf16();
+        }
+        utf16_offset
+    }
+
+    fn range_to_utf16(content: &str, range: &Range<usize>) -> Range<usize> {
+        Self::offset_to_utf16(content, range.start)..Self::offset_to_utf16(content, range.end)
+    }
+
+    fn range_from_utf16(content: &str, range_utf16: &Range<usize>) -> Range<usize> {
+        Self::offset_from_utf16(content, range_utf16.start)
+            ..Self::offset_from_utf16(content, range_utf16.end)
+    }
+
+    fn previous_boundary(&self, offset: usize, cx: &App) -> usize {
+        let content = self.content(cx);
+        content[..offset.min(content.len())]
+            .char_indices()
+            .last()
+            .map_or(0, |(index, _)| index)
+    }
+
+    fn next_boundary(&self, offset: usize, cx: &App) -> usize {
+        let content = self.content(cx);
+        content[offset.min(content.len())..]
+            .chars()
+            .next()
+            .map_or(content.len(), |c| offset + c.len_utf8())
+    }
+
+    /// 置き換える範囲。外で内容が変わっていても範囲外にならないようにする
+    fn target_range(&self, content: &str, range_utf16: Option<Range<usize>>) -> Range<usize> {
+        let range = range_utf16
+            .as_ref()
+            .map(|range_utf16| Self::range_from_utf16(content, range_utf16))
+            .or(self.marked_range.clone())
+            .unwrap_or(self.selected_range.clone());
+        range.start.min(content.len())..range.end.min(content.len())
+    }
+}
+
+impl EntityInputHandler for NumberInput {
+    fn text_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        actual_range: &mut Option<Range<usize>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let content = self.content(cx);
+        let range = self.target_range(&content, Some(range_utf16));
+        actual_range.replace(Self::range_to_utf16(&content, &range));
+        Some(content[range].to_string())
+    }
+
+    fn selected_text_range(
+        &mut self,
+        _ignore_disabled_input: bool,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<UTF16Selection> {
+        Some(UTF16Selection {
+            range: Self::range_to_utf16(&self.content(cx), &self.selected_range),
+            reversed: self.selection_reversed,
+        })
+    }
+
+    fn marked_text_range(
+        &self,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Range<usize>> {
+        let content = self.content(cx);
+        self.marked_range
+            .as_ref()
+            .map(|range| Self::range_to_utf16(&content, range))
+    }
+
+    fn unmark_text(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
+        self.marked_range = None;
+    }
+
+    /// 確定した文字は半角の数字だけにして、範囲の桁数までに切り詰める
+    fn replace_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let content = self.content(cx);
+        let range = self.target_range(&content, range_utf16);
+        let max_chars = self.state_ticket.read(cx).range().max_chars();
+        let kept = content[..range.start].chars().count() + content[range.end..].chars().count();
+        let new_text: String = normalize_number(new_text)
+            .chars()
+            .take(max_chars.saturating_sub(kept))
+            .collect();
+
+        let content = content[..range.start].to_owned() + &new_text + &content[range.end..];
+        self.selected_range = range.start + new_text.len()..range.start + new_text.len();
+        self.selection_reversed = false;
+        self.marked_range = None;
+        self.cursor_visible = true;
+        self.set_content(content, cx);
+        cx.notify();
+    }
+
+    /// 変換中の文字はそのまま見せて、確定したときに数字にする
+    fn replace_and_mark_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        new_selected_range_utf16: Option<Range<usize>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let content = self.content(cx);
+        let range = self.target_range(&content, range_utf16);
+        let content = content[..range.start].to_owned() + new_text + &content[range.end..];
+        self.marked_range =
+            (!new_text.is_empty()).then(|| range.start..range.start + new_text.len());
+        self.selected_range = new_selected_range_utf16
+            .as_ref()
+            .map(|range_utf16| Self::range_from_utf16(new_text, range_utf16))
+            .map(|new_range| new_range.start + range.start..new_range.end + range.start)
+            .unwrap_or_else(|| range.start + new_text.len()..range.start + new_text.len());
+        self.set_content(content, cx);
+        cx.notify();
+    }
+
+    fn bounds_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        bounds: Bounds<Pixels>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Bounds<Pixels>> {
+        let content = self.content(cx);
+        let last_layout = self.last_layout.as_ref()?;
+        let range = Self::range_from_utf16(&content, &range_utf16);
+        Some(Bounds::from_corners(
+            point(
+                bounds.left() + last_layout.x_for_index(range.start),
+                bounds.top(),
+            ),
+            point(
+                bounds.left() + last_layout.x_for_index(range.end),
+                bounds.bottom(),
+            ),
+        ))
+    }
+
+    fn character_index_for_point(
+        &mut self,
+        point: Point<Pixels>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        let line_point = self.last_bounds?.localize(&point)?;
+        let last_layout = self.last_layout.as_ref()?;
+        let utf8_index = last_layout.index_for_x(point.x - line_point.x)?;
+        Some(Self::offset_to_utf16(&self.content(cx), utf8_index))
+    }
+}
+
+impl Focusable for NumberInput {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for NumberInput {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focused = self.focus_handle.is_focused(window);
+        div()
+            .size_24()
+            .flex()
+            .items_center()
+            .px_1()
+            .bg(rgb(0x222222))
+            .border_2()
+            .border_color(if focused {
+                rgb(0xffc048)
+            } else {
+                rgb(0x007aff)
+            })
+            .text_color(rgb(0xffffff))
+            .key_context(INPUT_KEY_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .cursor(CursorStyle::IBeam)
+            .on_action(cx.listener(Self::backspace))
+            .on_action(cx.listener(Self::delete))
+            .on_action(cx.listener(Self::left))
+            .on_action(cx.listener(Self::right))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_all))
+            .on_action(cx.listener(Self::home))
+            .on_action(cx.listener(Self::end))
+            .on_action(cx.listener(Self::paste))
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::cut))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_move(cx.listener(Self::on_mouse_move))
+            .child(NumberInputElement { input: cx.entity() })
+    }
+}
+
+/// 文字とキャレット、選択範囲を描く
+struct NumberInputElement {
+    input: Entity<NumberInput>,
+}
+
+struct PrepaintState {
+    line: Option<ShapedLine>,
+    cursor: Option<PaintQuad>,
+    selection: Option<PaintQuad>,
+}
+
+impl IntoElement for NumberInputElement {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl Element for NumberInputElement {
+    type RequestLayoutState = ();
+    type PrepaintState = PrepaintState;
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&gpui::InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let mut style = Style::default();
+        style.size.width = relative(1.).into();
+        style.size.height = window.line_height().into();
+        (window.request_layout(style, [], cx), ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&gpui::InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self::PrepaintState {
+        let input = self.input.read(cx);
+        let content = input.content(cx);
+        let selected_range = input.selected_range.start.min(content.len())
+            ..input.selected_range.end.min(content.len());
+        let cursor = input.cursor_offset().min(content.len());
+        let style = window.text_style();
+
+        let run = TextRun {
+            len: content.len(),
+            font: style.font(),
+            color: style.color,
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        // 変換中の部分には下線を引く
+        let runs = match input.marked_range.as_ref() {
+            Some(marked_range) if marked_range.end <= content.len() => vec![
+                TextRun {
+                    len: marked_range.start,
+                    ..run.clone()
+                },
+                TextRun {
+                    len: marked_range.end - marked_range.start,
+                    underline: Some(UnderlineStyle {
+                        color: Some(run.color),
+                        thickness: px(1.0),
+                        wavy: false,
+                    }),
+                    ..run.clone()
+                },
+                TextRun {
+                    len: content.len() - marked_range.end,
+                    ..run
+                },
+            ]
+            .into_iter()
+            .filter(|run| run.len > 0)
+            .collect(),
+            _ => vec![run],
+        };
+
+        let font_size = style.font_size.to_pixels(window.rem_size());
+        let line = window
+            .text_system()
+            .shape_line(content, font_size, &runs, None);
+
+        let (selection, cursor) = if selected_range.is_empty() {
+            let cursor_pos = line.x_for_index(cursor);
+            (
+                None,
+                input.cursor_visible.then(|| {
+                    fill(
+                        Bounds::new(
+                            point(bounds.left() + cursor_pos, bounds.top()),
+                            size(px(2.), bounds.bottom() - bounds.top()),
+                        ),
+                        rgb(0x7ec8ff),
+                    )
+                }),
+            )
+        } else {
+            (
+                Some(fill(
+                    Bounds::from_corners(
+                        point(
+                            bounds.left() + line.x_for_index(selected_range.start),
+                            bounds.top(),
+                        ),
+                        point(
+                            bounds.left() + line.x_for_index(selected_range.end),
+                            bounds.bottom(),
+                        ),
+                    ),
+                    rgba(0x007aff60),
+                )),
+                None,
+            )
+        };
+        PrepaintState {
+            line: Some(line),
+            cursor,
+            selection,
+        }
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&gpui::InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        prepaint: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let focus_handle = self.input.read(cx).focus_handle.clone();
+        window.handle_input(
+            &focus_handle,
+            ElementInputHandler::new(bounds, self.input.clone()),
+            cx,
+        );
+        if let Some(selection) = prepaint.selection.take() {
+            window.paint_quad(selection)
+        }
+        let line = prepaint.line.take().unwrap();
+        line.paint(bounds.origin, window.line_height(), window, cx)
+            .unwrap();
+
+        if focus_handle.is_focused(window)
+            && let Some(cursor) = prepaint.cursor.take()
+        {
+            window.paint_quad(cursor);
+        }
+
+        self.input.update(cx, |input, _cx| {
+            input.last_layout = Some(line);
+            input.last_bounds = Some(bounds);
+        });
+    }
+}
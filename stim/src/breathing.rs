@@ -0,0 +1,315 @@
+use gpui::{AsyncApp, Context, Task, WeakEntity};
+use std::f32::consts::PI;
+use std::time::{Duration, Instant};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BreathingStatus {
+    Idle,
+    Running,
+    Finished,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BreathPhase {
+    Inhale,
+    HoldIn,
+    Exhale,
+    HoldOut,
+}
+
+impl BreathPhase {
+    pub const ALL: [BreathPhase; 4] = [
+        BreathPhase::Inhale,
+        BreathPhase::HoldIn,
+        BreathPhase::Exhale,
+        BreathPhase::HoldOut,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BreathPhase::Inhale => "吸う",
+            BreathPhase::HoldIn | BreathPhase::HoldOut => "止める",
+            BreathPhase::Exhale => "吐く",
+        }
+    }
+
+    fn cue_freq(&self) -> f32 {
+        match self {
+            BreathPhase::Inhale => 523.25,
+            BreathPhase::HoldIn | BreathPhase::HoldOut => 440.0,
+            BreathPhase::Exhale => 392.0,
+        }
+    }
+}
+
+/// 吸う・止める・吐く・止めるの秒数
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct BreathingPattern {
+    pub name: &'static str,
+    pub secs: [u32; 4],
+}
+
+pub const BREATHING_PATTERNS: [BreathingPattern; 3] = [
+    BreathingPattern {
+        name: "Box",
+        secs: [4, 4, 4, 4],
+    },
+    BreathingPattern {
+        name: "4-7-8",
+        secs: [4, 7, 8, 0],
+    },
+    BreathingPattern {
+        name: "Calm",
+        secs: [4, 0, 6, 0],
+    },
+];
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SessionLength {
+    Cycles(u32),
+    Minutes(u32),
+}
+
+pub const SESSION_LENGTHS: [SessionLength; 5] = [
+    SessionLength::Cycles(4),
+    SessionLength::Cycles(8),
+    SessionLength::Minutes(1),
+    SessionLength::Minutes(3),
+    SessionLength::Minutes(5),
+];
+
+pub struct BreathingModel {
+    pub status: BreathingStatus,
+    pub pattern: BreathingPattern,
+    pub pattern_index: usize,
+    pub length_index: usize,
+    pub sound_enabled: bool,
+    pub start_instant: Option<Instant>,
+    pub last_phase: Option<(u32, BreathPhase)>,
+    pub _tick_task: Option<Task<()>>,
+}
+
+impl BreathingModel {
+    pub fn new() -> Self {
+        BreathingModel {
+            status: BreathingStatus::Idle,
+            pattern: BREATHING_PATTERNS[0],
+            pattern_index: 0,
+            length_index: 0,
+            sound_enabled: true,
+            start_instant: None,
+            last_phase: None,
+            _tick_task: None,
+        }
+    }
+
+    pub fn session_length(&self) -> SessionLength {
+        SESSION_LENGTHS[self.length_index]
+    }
+
+    pub fn cycle_secs(&self) -> f32 {
+        self.pattern.secs.iter().sum::<u32>() as f32
+    }
+
+    /// 分指定のときも途中で切らずに、サイクルの区切りで終える
+    pub fn total_cycles(&self) -> u32 {
+        match self.session_length() {
+            SessionLength::Cycles(cycles) => cycles,
+            SessionLength::Minutes(minutes) => {
+                ((minutes * 60) as f32 / self.cycle_secs()).ceil() as u32
+            }
+        }
+    }
+
+    pub fn start(&mut self, cx: &mut Context<Self>) {
+        if self.cycle_secs() <= 0. {
+            return;
+        }
+        self.status = BreathingStatus::Running;
+        self.start_instant = Some(Instant::now());
+        self.last_phase = None;
+        self.breathing_timer(cx);
+        cx.notify();
+    }
+
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        self.status = BreathingStatus::Idle;
+        self.start_instant = None;
+        self.last_phase = None;
+        self._tick_task = None;
+        cx.notify();
+    }
+
+    pub fn toggle(&mut self, cx: &mut Context<Self>) {
+        match self.status {
+            BreathingStatus::Idle => self.start(cx),
+            BreathingStatus::Running | BreathingStatus::Finished => self.stop(cx),
+        }
+    }
+
+    pub fn cycle_pattern(&mut self, cx: &mut Context<Self>) {
+        if self.status == BreathingStatus::Running {
+            return;
+        }
+        self.pattern_index = (self.pattern_index + 1) % BREATHING_PATTERNS.len();
+        self.pattern = BREATHING_PATTERNS[self.pattern_index];
+        cx.notify();
+    }
+
+    /// 各フェーズの秒数を1秒ずつ増やし、12秒を超えたら最小に戻す。
+    /// 止めるフェーズは0秒(省略)にできる
+    pub fn bump_phase_secs(&mut self, phase: BreathPhase, cx: &mut Context<Self>) {
+        if self.status == BreathingStatus::Running {
+            return;
+        }
+        let index = phase as usize;
+        let min = match phase {
+            BreathPhase::Inhale | BreathPhase::Exhale => 1,
+            BreathPhase::HoldIn | BreathPhase::HoldOut => 0,
+        };
+        let secs = self.pattern.secs[index] + 1;
+        self.pattern.secs[index] = if secs > 12 { min } else { secs };
+        self.pattern.name = "Custom";
+        cx.notify();
+    }
+
+    pub fn cycle_session_length(&mut self, cx: &mut Context<Self>) {
+        if self.status == BreathingStatus::Running {
+            return;
+        }
+        self.length_index = (self.length_index + 1) % SESSION_LENGTHS.len();
+        cx.notify();
+    }
+
+    /// プリセットの時間に一番近い、分で決めた長さにする
+    pub fn set_session_secs(&mut self, secs: u32, cx: &mut Context<Self>) {
+        if self.status == BreathingStatus::Running {
+            return;
+        }
+        let nearest = SESSION_LENGTHS
+            .iter()
+            .enumerate()
+            .filter_map(|(index, length)| match length {
+                SessionLength::Minutes(minutes) => Some((index, (minutes * 60).abs_diff(secs))),
+                SessionLength::Cycles(_) => None,
+            })
+            .min_by_key(|(_, diff)| *diff);
+        if let Some((index, _)) = nearest {
+            self.length_index = index;
+            cx.notify();
+        }
+    }
+
+    pub fn toggle_sound(&mut self, cx: &mut Context<Self>) {
+        self.sound_enabled = !self.sound_enabled;
+        cx.notify();
+    }
+
+    pub fn elapsed_secs(&self) -> f32 {
+        self.start_instant
+            .map(|start_time| start_time.elapsed().as_secs_f32())
+            .unwrap_or(0.)
+    }
+
+    /// 経過時間から、何サイクル目のどのフェーズか、そのフェーズの進み具合を求める
+    pub fn phase_at(&self, elapsed: f32) -> (u32, BreathPhase, f32) {
+        let cycle_secs = self.cycle_secs();
+        let cycle = (elapsed / cycle_secs) as u32;
+        let mut in_cycle = elapsed - cycle as f32 * cycle_secs;
+        for phase in BreathPhase::ALL {
+            let secs = self.pattern.secs[phase as usize] as f32;
+            if secs > 0. && in_cycle < secs {
+                return (cycle, phase, in_cycle / secs);
+            }
+            in_cycle -= secs;
+        }
+        (cycle, BreathPhase::Inhale, 0.)
+    }
+
+    pub fn current_phase(&self) -> Option<(u32, BreathPhase, f32)> {
+        match self.status {
+            BreathingStatus::Running => Some(self.phase_at(self.elapsed_secs())),
+            _ => None,
+        }
+    }
+
+    /// 輪の大きさ(0.0..1.0)。吸う間に広がり、吐く間に縮む
+    pub fn ring_scale(&self) -> f32 {
+        const MIN_SCALE: f32 = 0.4;
+        let Some((_, phase, progress)) = self.current_phase() else {
+            return 1.0;
+        };
+        // 端でゆっくりになるように余弦で補間する
+        let eased = (1.0 - (PI * progress).cos()) / 2.0;
+        match phase {
+            BreathPhase::Inhale => MIN_SCALE + (1.0 - MIN_SCALE) * eased,
+            BreathPhase::HoldIn => 1.0,
+            BreathPhase::Exhale => 1.0 - (1.0 - MIN_SCALE) * eased,
+            BreathPhase::HoldOut => MIN_SCALE,
+        }
+    }
+
+    pub fn play_phase_cue(phase: BreathPhase) {
+        use rodio::{OutputStream, Sink, source::*};
+
+        std::thread::spawn(move || {
+            let Ok((_stream, stream_handle)) = OutputStream::try_default() else {
+                return;
+            };
+            let Ok(sink) = Sink::try_new(&stream_handle) else {
+                return;
+            };
+            let mut cue = SineWave::new(phase.cue_freq())
+                .fade_in(Duration::from_millis(120))
+                .take_duration(Duration::from_millis(600));
+            cue.set_filter_fadeout();
+            sink.append(cue.amplify(0.08));
+            sink.sleep_until_end();
+        });
+    }
+
+    pub fn breathing_timer(&mut self, cx: &mut Context<BreathingModel>) {
+        if self._tick_task.is_some() {
+            return;
+        }
+        self._tick_task = Some(
+            cx.spawn(|we: WeakEntity<BreathingModel>, cx_ref: &mut AsyncApp| {
+                let mut cx = cx_ref.clone();
+                async move {
+                    loop {
+                        cx.background_executor()
+                            .timer(Duration::from_millis(100))
+                            .await;
+                        let should_stop = we
+                            .update(&mut cx, |this, model_cx| {
+                                if this.status != BreathingStatus::Running {
+                                    return true;
+                                }
+                                let (cycle, phase, _) = this.phase_at(this.elapsed_secs());
+                                if cycle >= this.total_cycles() {
+                                    this.status = BreathingStatus::Finished;
+                                    this.start_instant = None;
+                                    this._tick_task = None;
+                                    model_cx.notify();
+                                    return true;
+                                }
+                                if this.last_phase != Some((cycle, phase)) {
+                                    if this.sound_enabled {
+                                        BreathingModel::play_phase_cue(phase);
+                                    }
+                                    this.last_phase = Some((cycle, phase));
+                                    model_cx.notify();
+                                }
+                                false
+                            })
+                            .unwrap_or(true);
+
+                        if should_stop {
+                            break;
+                        }
+                    }
+                }
+            }),
+        )
+    }
+}
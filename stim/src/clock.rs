@@ -0,0 +1,281 @@
+use crate::model::TimerModel;
+use crate::storage;
+use chrono::{DateTime, Local, Offset, TimeZone, Timelike, Utc};
+use chrono_tz::{TZ_VARIANTS, Tz};
+use gpui::{AsyncApp, Context, Task, WeakEntity};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 指定したゾーンの時刻に向けたカウントダウン
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ClockTarget {
+    pub zone: Tz,
+    pub hour: u32,
+    pub minute: u32,
+    pub at: DateTime<Utc>,
+}
+
+pub struct WorldClockModel {
+    pub zones: Vec<Tz>,
+    pub selected: usize,
+    pub zone_query: String,
+    pub target_digits: u32,
+    pub target: Option<ClockTarget>,
+    pub message: String,
+    pub active: bool,
+    pub _tick_task: Option<Task<()>>,
+}
+
+impl WorldClockModel {
+    pub fn new() -> Self {
+        let (zones, message) = match Self::load() {
+            Ok(Some(zones)) => (zones, String::new()),
+            Ok(None) => (Self::default_zones(), String::new()),
+            Err(err) => (
+                Self::default_zones(),
+                format!("ゾーンの読み込みに失敗: {}", err),
+            ),
+        };
+        WorldClockModel {
+            zones,
+            selected: 0,
+            zone_query: String::new(),
+            target_digits: 0,
+            target: None,
+            message,
+            active: false,
+            _tick_task: None,
+        }
+    }
+
+    fn default_zones() -> Vec<Tz> {
+        vec![
+            Tz::UTC,
+            chrono_tz::America::New_York,
+            chrono_tz::Europe::London,
+            chrono_tz::Asia::Tokyo,
+        ]
+    }
+
+    /// $XDG_CONFIG_HOME/stim/zones.json (なければ ~/.config/stim/zones.json)
+    /// 例: ["UTC", "Asia/Tokyo"]
+    pub fn config_path() -> Option<PathBuf> {
+        storage::xdg_path("XDG_CONFIG_HOME", ".config", "zones.json")
+    }
+
+    fn load() -> Result<Option<Vec<Tz>>, String> {
+        let Some(path) = Self::config_path() else {
+            return Ok(None);
+        };
+        let Some(names) = storage::load_json::<Vec<String>>(&path)? else {
+            return Ok(None);
+        };
+        names
+            .iter()
+            .map(|name| {
+                name.parse::<Tz>()
+                    .map_err(|_| format!("知らないゾーン {}", name))
+            })
+            .collect::<Result<Vec<Tz>, String>>()
+            .map(Some)
+    }
+
+    fn save(&mut self) {
+        let Some(path) = Self::config_path() else {
+            return;
+        };
+        let names: Vec<&str> = self.zones.iter().map(|zone| zone.name()).collect();
+        if let Err(err) = storage::save_json(&path, &names) {
+            self.message = format!("ゾーンの保存に失敗: {}", err);
+        }
+    }
+
+    /// "Asia/Tokyo" のほか、"tokyo" や "new york" のような都市名だけでも探す
+    pub fn find_zone(query: &str) -> Option<Tz> {
+        let query = query.trim().replace(' ', "_");
+        if query.is_empty() {
+            return None;
+        }
+        TZ_VARIANTS.iter().copied().find(|zone| {
+            let name = zone.name();
+            name.eq_ignore_ascii_case(&query)
+                || name
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|city| city.eq_ignore_ascii_case(&query))
+        })
+    }
+
+    pub fn push_query_char(&mut self, c: char, cx: &mut Context<Self>) {
+        self.zone_query.push(c);
+        cx.notify();
+    }
+
+    pub fn push_digit(&mut self, digit: u8, cx: &mut Context<Self>) {
+        self.target_digits = (self.target_digits * 10 + digit as u32) % 10000;
+        cx.notify();
+    }
+
+    pub fn pop_input(&mut self, cx: &mut Context<Self>) {
+        if self.zone_query.pop().is_none() {
+            self.target_digits /= 10;
+        }
+        cx.notify();
+    }
+
+    pub fn add_zone(&mut self, cx: &mut Context<Self>) {
+        match Self::find_zone(&self.zone_query) {
+            Some(zone) if self.zones.contains(&zone) => {
+                self.message = format!("{} は追加済み", zone.name());
+            }
+            Some(zone) => {
+                self.zones.push(zone);
+                self.selected = self.zones.len() - 1;
+                self.message.clear();
+                self.save();
+            }
+            None => {
+                self.message = format!("{} が見つからない", self.zone_query);
+            }
+        }
+        self.zone_query.clear();
+        cx.notify();
+    }
+
+    pub fn remove_zone(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index >= self.zones.len() {
+            return;
+        }
+        self.zones.remove(index);
+        self.selected = self.selected.min(self.zones.len().saturating_sub(1));
+        self.save();
+        cx.notify();
+    }
+
+    pub fn select_zone(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index < self.zones.len() {
+            self.selected = index;
+            cx.notify();
+        }
+    }
+
+    /// 入力した HHMM を選択中のゾーンの時刻として、次にその時刻になる瞬間を目標にする
+    pub fn set_target(&mut self, cx: &mut Context<Self>) {
+        let Some(&zone) = self.zones.get(self.selected) else {
+            return;
+        };
+        let (hour, minute) = (self.target_digits / 100, self.target_digits % 100);
+        self.target_digits = 0;
+        match Self::next_occurrence(zone, hour, minute, Utc::now()) {
+            Some(at) => {
+                self.target = Some(ClockTarget {
+                    zone,
+                    hour,
+                    minute,
+                    at,
+                });
+                self.message.clear();
+                self.clock_timer(cx);
+            }
+            None => {
+                self.message = format!("{:02}:{:02} は無効な時刻", hour, minute);
+            }
+        }
+        cx.notify();
+    }
+
+    pub fn clear_target(&mut self, cx: &mut Context<Self>) {
+        self.target = None;
+        cx.notify();
+    }
+
+    pub fn next_occurrence(
+        zone: Tz,
+        hour: u32,
+        minute: u32,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let local_now = now.with_timezone(&zone);
+        let mut date = local_now.date_naive();
+        // 今日の時刻が過ぎていれば明日。夏時間で存在しない時刻ならさらに次の日
+        for _ in 0..3 {
+            let naive = date.and_hms_opt(hour, minute, 0)?;
+            if let Some(at) = zone.from_local_datetime(&naive).earliest()
+                && at > local_now
+            {
+                return Some(at.with_timezone(&Utc));
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    pub fn target_remaining_secs(&self) -> Option<i64> {
+        self.target
+            .map(|target| (target.at - Utc::now()).num_seconds().max(0))
+    }
+
+    /// ローカル時刻との差(秒)
+    pub fn offset_from_local(zone: Tz, now: DateTime<Utc>) -> i32 {
+        let zone_offset = now.with_timezone(&zone).offset().fix().local_minus_utc();
+        let local_offset = now.with_timezone(&Local).offset().local_minus_utc();
+        zone_offset - local_offset
+    }
+
+    pub fn is_daytime(zone: Tz, now: DateTime<Utc>) -> bool {
+        (6..18).contains(&now.with_timezone(&zone).hour())
+    }
+
+    /// 時計の表示とカウントダウンのために定期的に再描画する
+    pub fn clock_timer(&mut self, cx: &mut Context<WorldClockModel>) {
+        if self._tick_task.is_some() {
+            return;
+        }
+        self._tick_task = Some(cx.spawn(
+            |we: WeakEntity<WorldClockModel>, cx_ref: &mut AsyncApp| {
+                let mut cx = cx_ref.clone();
+                async move {
+                    loop {
+                        cx.background_executor()
+                            .timer(Duration::from_millis(250))
+                            .await;
+                        let should_stop = we
+                            .update(&mut cx, |this, model_cx| {
+                                if let Some(target) = this.target
+                                    && target.at <= Utc::now()
+                                {
+                                    this.message = format!(
+                                        "{} {:02}:{:02} になった",
+                                        target.zone.name(),
+                                        target.hour,
+                                        target.minute
+                                    );
+                                    this.target = None;
+                                    TimerModel::play_finish_sound(None);
+                                }
+                                model_cx.notify();
+                                // 時計タブを離れていて、カウントダウンもなければ止める
+                                if !this.active && this.target.is_none() {
+                                    this._tick_task = None;
+                                    return true;
+                                }
+                                false
+                            })
+                            .unwrap_or(true);
+
+                        if should_stop {
+                            break;
+                        }
+                    }
+                }
+            },
+        ))
+    }
+
+    pub fn set_active(&mut self, active: bool, cx: &mut Context<Self>) {
+        self.active = active;
+        if active {
+            self.clock_timer(cx);
+        }
+    }
+}
@@ -1,19 +1,26 @@
 use gpui::{App, AppContext, Application, Bounds, WindowBounds, WindowOptions, px, size};
 
+mod breathing;
+mod clock;
+mod metronome;
+mod mini_view;
 mod model;
+mod preset;
+mod storage;
 mod view;
 
 use view::TimerView;
 
 fn main() {
     Application::new().run(|cx: &mut App| {
-        let bounds = Bounds::centered(None, size(px(300.), px(300.)), cx);
+        let bounds = Bounds::centered(None, size(px(300.), px(360.)), cx);
         cx.open_window(
             WindowOptions {
                 window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_min_size: Some(size(px(240.), px(300.))),
                 ..Default::default()
             },
-            |_, cx| cx.new(|view_cx| TimerView::new(view_cx)),
+            |_, cx| cx.new(TimerView::new),
         )
         .expect("Failed to open main window");
     });
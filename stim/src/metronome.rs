@@ -0,0 +1,290 @@
+use gpui::Context;
+use rodio::Source;
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+pub const MIN_BPM: u32 = 20;
+pub const MAX_BPM: u32 = 300;
+
+const SAMPLE_RATE: u32 = 48_000;
+const CLICK_SECS: f32 = 0.03;
+// この間隔より空いたタップは新しいテンポの入力とみなす
+const TAP_RESET: Duration = Duration::from_secs(2);
+const TAP_WINDOW: usize = 5;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MetronomeStatus {
+    Stopped,
+    Playing,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct TimeSignature {
+    pub beats: u32,
+    pub unit: u32,
+}
+
+pub const TIME_SIGNATURES: [TimeSignature; 6] = [
+    TimeSignature { beats: 4, unit: 4 },
+    TimeSignature { beats: 3, unit: 4 },
+    TimeSignature { beats: 2, unit: 4 },
+    TimeSignature { beats: 5, unit: 4 },
+    TimeSignature { beats: 6, unit: 8 },
+    TimeSignature { beats: 7, unit: 8 },
+];
+
+/// オーディオスレッドとUIで共有する状態。
+/// 拍のタイミングはオーディオ側のサンプル数だけで決まり、UIは読むだけ。
+struct MetronomeClock {
+    running: AtomicBool,
+    bpm: AtomicU32,
+    beats_per_bar: AtomicU32,
+    current_beat: AtomicU32,
+    // 拍の中での位置(0.0..1.0)をf32のビット列で持つ
+    beat_phase: AtomicU32,
+}
+
+pub struct MetronomeModel {
+    pub status: MetronomeStatus,
+    pub bpm: u32,
+    pub bpm_digits: Option<u32>,
+    pub signature_index: usize,
+    pub tap_instants: Vec<Instant>,
+    clock: Option<Arc<MetronomeClock>>,
+}
+
+impl MetronomeModel {
+    pub fn new() -> Self {
+        MetronomeModel {
+            status: MetronomeStatus::Stopped,
+            bpm: 120,
+            bpm_digits: None,
+            signature_index: 0,
+            tap_instants: Vec::new(),
+            clock: None,
+        }
+    }
+
+    pub fn signature(&self) -> TimeSignature {
+        TIME_SIGNATURES[self.signature_index]
+    }
+
+    pub fn start(&mut self, cx: &mut Context<Self>) {
+        self.commit_bpm(cx);
+        let clock = Arc::new(MetronomeClock {
+            running: AtomicBool::new(true),
+            bpm: AtomicU32::new(self.bpm),
+            beats_per_bar: AtomicU32::new(self.signature().beats),
+            current_beat: AtomicU32::new(0),
+            beat_phase: AtomicU32::new(0),
+        });
+        Self::spawn_click_stream(clock.clone());
+        self.clock = Some(clock);
+        self.status = MetronomeStatus::Playing;
+        cx.notify();
+    }
+
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        self.stop_clock();
+        self.status = MetronomeStatus::Stopped;
+        cx.notify();
+    }
+
+    pub fn toggle(&mut self, cx: &mut Context<Self>) {
+        match self.status {
+            MetronomeStatus::Stopped => self.start(cx),
+            MetronomeStatus::Playing => self.stop(cx),
+        }
+    }
+
+    pub fn push_digit(&mut self, digit: u8, cx: &mut Context<Self>) {
+        let digits = self.bpm_digits.unwrap_or(0);
+        self.bpm_digits = Some((digits * 10 + digit as u32) % 1000);
+        cx.notify();
+    }
+
+    pub fn pop_digit(&mut self, cx: &mut Context<Self>) {
+        let digits = self.bpm_digits.unwrap_or(self.bpm);
+        self.bpm_digits = Some(digits / 10);
+        cx.notify();
+    }
+
+    pub fn commit_bpm(&mut self, cx: &mut Context<Self>) {
+        if let Some(digits) = self.bpm_digits.take() {
+            self.set_bpm(digits, cx);
+        }
+    }
+
+    pub fn set_bpm(&mut self, bpm: u32, cx: &mut Context<Self>) {
+        self.bpm = bpm.clamp(MIN_BPM, MAX_BPM);
+        self.bpm_digits = None;
+        if let Some(clock) = &self.clock {
+            clock.bpm.store(self.bpm, Ordering::Relaxed);
+        }
+        cx.notify();
+    }
+
+    pub fn tap(&mut self, cx: &mut Context<Self>) {
+        let now = Instant::now();
+        if let Some(last) = self.tap_instants.last()
+            && now.duration_since(*last) > TAP_RESET
+        {
+            self.tap_instants.clear();
+        }
+        self.tap_instants.push(now);
+        if self.tap_instants.len() > TAP_WINDOW {
+            self.tap_instants.remove(0);
+        }
+        if let [first, .., last] = self.tap_instants[..] {
+            let interval =
+                last.duration_since(first).as_secs_f32() / (self.tap_instants.len() - 1) as f32;
+            self.set_bpm((60.0 / interval).round() as u32, cx);
+        }
+        cx.notify();
+    }
+
+    pub fn cycle_signature(&mut self, cx: &mut Context<Self>) {
+        self.signature_index = (self.signature_index + 1) % TIME_SIGNATURES.len();
+        if let Some(clock) = &self.clock {
+            clock
+                .beats_per_bar
+                .store(self.signature().beats, Ordering::Relaxed);
+        }
+        cx.notify();
+    }
+
+    /// 小節内の現在の拍と、その拍の中の進み具合
+    pub fn current_beat(&self) -> Option<(u32, f32)> {
+        let clock = self.clock.as_ref()?;
+        let beat = clock.current_beat.load(Ordering::Relaxed);
+        let phase = f32::from_bits(clock.beat_phase.load(Ordering::Relaxed));
+        Some((beat, phase))
+    }
+
+    /// オーディオスレッドに止まるよう伝える。スレッドが出力ストリームを閉じる
+    fn stop_clock(&mut self) {
+        if let Some(clock) = self.clock.take() {
+            clock.running.store(false, Ordering::Relaxed);
+        }
+    }
+
+    fn spawn_click_stream(clock: Arc<MetronomeClock>) {
+        use rodio::{OutputStream, Sink};
+
+        std::thread::spawn(move || {
+            let Ok((stream, stream_handle)) = OutputStream::try_default() else {
+                return;
+            };
+            let Ok(sink) = Sink::try_new(&stream_handle) else {
+                return;
+            };
+            sink.append(ClickSource::new(clock.clone()));
+            // 音が止まって読まれなくなっても抜けられるように、止める合図を自分で見る
+            while clock.running.load(Ordering::Relaxed) && !sink.empty() {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            sink.stop();
+            drop(stream);
+        });
+    }
+}
+
+impl Drop for MetronomeModel {
+    fn drop(&mut self) {
+        self.stop_clock();
+    }
+}
+
+/// クリック音をサンプル単位で生成するSource。
+/// 拍の長さをサンプル数で数えるので、UIのポーリング間隔に左右されない。
+struct ClickSource {
+    clock: Arc<MetronomeClock>,
+    beat_in_bar: u32,
+    sample_in_beat: u32,
+    samples_per_beat: u32,
+    click_freq: f32,
+    click_amp: f32,
+}
+
+impl ClickSource {
+    fn new(clock: Arc<MetronomeClock>) -> Self {
+        let mut source = ClickSource {
+            clock,
+            beat_in_bar: 0,
+            sample_in_beat: 0,
+            samples_per_beat: 0,
+            click_freq: 0.,
+            click_amp: 0.,
+        };
+        source.begin_beat();
+        source
+    }
+
+    fn begin_beat(&mut self) {
+        let bpm = self.clock.bpm.load(Ordering::Relaxed).max(1);
+        let beats_per_bar = self.clock.beats_per_bar.load(Ordering::Relaxed).max(1);
+        if self.beat_in_bar >= beats_per_bar {
+            self.beat_in_bar = 0;
+        }
+        self.samples_per_beat = SAMPLE_RATE * 60 / bpm;
+        self.sample_in_beat = 0;
+        // 小節の頭だけ高く大きい音にする
+        (self.click_freq, self.click_amp) = if self.beat_in_bar == 0 {
+            (1760.0, 0.5)
+        } else {
+            (880.0, 0.3)
+        };
+        self.clock
+            .current_beat
+            .store(self.beat_in_bar, Ordering::Relaxed);
+    }
+}
+
+impl Iterator for ClickSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if !self.clock.running.load(Ordering::Relaxed) {
+            return None;
+        }
+        if self.sample_in_beat >= self.samples_per_beat {
+            self.beat_in_bar += 1;
+            self.begin_beat();
+        }
+
+        let t = self.sample_in_beat as f32 / SAMPLE_RATE as f32;
+        let sample = if t < CLICK_SECS {
+            let envelope = 1.0 - t / CLICK_SECS;
+            (TAU * self.click_freq * t).sin() * self.click_amp * envelope * envelope
+        } else {
+            0.0
+        };
+
+        self.sample_in_beat += 1;
+        let phase = self.sample_in_beat as f32 / self.samples_per_beat as f32;
+        self.clock
+            .beat_phase
+            .store(phase.to_bits(), Ordering::Relaxed);
+        Some(sample)
+    }
+}
+
+impl Source for ClickSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
@@ -0,0 +1,154 @@
+use crate::model::{TimerModel, TimerStatus};
+use gpui::{
+    AnyWindowHandle, App, AppContext, Bounds, Context, Entity, FocusHandle, IntoElement,
+    MouseButton, Render, Window, WindowBounds, WindowDecorations, WindowKind, WindowOptions, div,
+    prelude::*, px, relative, rgb, size,
+};
+
+/// 作業中も邪魔にならない、残り時間と細いバーだけの小窓
+pub struct MiniTimerView {
+    timer_ticket: Entity<TimerModel>,
+    main_window: AnyWindowHandle,
+    focus_handle: FocusHandle,
+}
+
+impl MiniTimerView {
+    /// メインウィンドウを最小化して、常に手前に表示される小窓を開く。
+    /// もう開いていれば、新しく開かずにその小窓を前に出す
+    pub fn open(timer_ticket: Entity<TimerModel>, window: &mut Window, cx: &mut App) {
+        if let Some(mini_window) = timer_ticket.read(cx).mini_window
+            && mini_window
+                .update(cx, |_, mini_window, _| mini_window.activate_window())
+                .is_ok()
+        {
+            return;
+        }
+        let main_window = window.window_handle();
+        let bounds = Bounds::centered(None, size(px(180.), px(56.)), cx);
+        let opened = cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                titlebar: None,
+                kind: WindowKind::PopUp,
+                is_resizable: false,
+                window_decorations: Some(WindowDecorations::Client),
+                ..Default::default()
+            },
+            |window, cx| {
+                cx.new(|view_cx| {
+                    let focus_handle = view_cx.focus_handle();
+                    window.focus(&focus_handle);
+                    MiniTimerView {
+                        timer_ticket: timer_ticket.clone(),
+                        main_window,
+                        focus_handle,
+                    }
+                })
+            },
+        );
+        if let Ok(mini_window) = opened {
+            timer_ticket.update(cx, |timer_model, _| {
+                timer_model.mini_window = Some(mini_window)
+            });
+            window.minimize_window();
+        }
+    }
+
+    /// 小窓を閉じてメインウィンドウに戻る
+    fn close(
+        timer_ticket: &Entity<TimerModel>,
+        main_window: AnyWindowHandle,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        timer_ticket.update(cx, |timer_model, _| timer_model.mini_window = None);
+        window.remove_window();
+        main_window
+            .update(cx, |_, main_window, _| main_window.activate_window())
+            .ok();
+    }
+
+    fn remaining_label(time: &TimerModel) -> String {
+        if time.status == TimerStatus::Idle {
+            return format!(
+                "{:02}:{:02}:{:02}",
+                time.display_hours, time.display_minutes, time.display_seconds
+            );
+        }
+        let remaining = time.current_remaining().ceil() as u32;
+        let (hours, minutes, seconds) = (remaining / 3600, remaining % 3600 / 60, remaining % 60);
+        if hours > 0 {
+            format!("{}:{:02}:{:02}", hours, minutes, seconds)
+        } else {
+            format!("{}:{:02}", minutes, seconds)
+        }
+    }
+}
+
+impl Render for MiniTimerView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let timer_model = self.timer_ticket.read(cx);
+        let main_window = self.main_window;
+        let timer_ticket = self.timer_ticket.clone();
+
+        let background_color = match timer_model.status {
+            TimerStatus::Paused => rgb(0xd6dce0),
+            TimerStatus::Finished => rgb(0xd76a1d),
+            _ => rgb(0xd6e0d6),
+        };
+
+        if timer_model.status == TimerStatus::Running {
+            cx.on_next_frame(window, move |_model, _window, cx| {
+                cx.notify();
+            });
+        }
+        div()
+            .track_focus(&self.focus_handle)
+            .flex()
+            .flex_col()
+            .justify_center()
+            .size_full()
+            .px(px(12.))
+            .gap(px(4.))
+            .bg(background_color)
+            .on_mouse_down(MouseButton::Left, {
+                let timer_ticket = timer_ticket.clone();
+                move |event, window, app_cx| {
+                    // ダブルクリックで元に戻す。シングルクリックはドラッグで移動
+                    if event.click_count >= 2 {
+                        Self::close(&timer_ticket, main_window, window, app_cx);
+                    } else {
+                        window.start_window_move();
+                    }
+                }
+            })
+            .on_key_down(move |event, window, app_cx| {
+                let key = event.keystroke.key.as_str();
+                if key == "m" || key == "escape" {
+                    Self::close(&timer_ticket, main_window, window, app_cx);
+                }
+            })
+            .child(
+                div()
+                    .flex()
+                    .justify_center()
+                    .text_size(px(22.0))
+                    .text_color(rgb(0x1b2635))
+                    .child(Self::remaining_label(timer_model)),
+            )
+            .child(
+                div()
+                    .w_full()
+                    .h(px(3.))
+                    .rounded(px(2.))
+                    .bg(rgb(0xb5c4b5))
+                    .child(
+                        div()
+                            .h_full()
+                            .rounded(px(2.))
+                            .w(relative(timer_model.gen_progress_ratio().clamp(0., 1.)))
+                            .bg(rgb(0x4a5c4a)),
+                    ),
+            )
+    }
+}
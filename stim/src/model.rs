@@ -1,4 +1,7 @@
-use gpui::{AsyncApp, Context, Task, WeakEntity};
+use crate::mini_view::MiniTimerView;
+use crate::preset::Preset;
+use gpui::{AsyncApp, Context, Task, WeakEntity, WindowHandle};
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
@@ -20,6 +23,9 @@ pub struct TimerModel {
     pub total_secs: f32,
     pub elapsed_secs: u32,
     pub total_elapsed_secs: f32,
+    pub finish_sound: Option<PathBuf>,
+    /// 開いている小窓。二重に開かないように覚えておく
+    pub mini_window: Option<WindowHandle<MiniTimerView>>,
     pub _timer_task: Option<Task<()>>,
 }
 
@@ -36,6 +42,8 @@ impl TimerModel {
             total_secs: 0.,
             elapsed_secs: 0,
             total_elapsed_secs: 0.,
+            finish_sound: None,
+            mini_window: None,
             _timer_task: None,
         }
     }
@@ -76,22 +84,38 @@ impl TimerModel {
         self.total_elapsed_secs = 0.;
         self.base_secs = 0.;
         self.start_instant = None;
+        self.finish_sound = None;
         self.notify_digits_display(cx);
     }
 
+    /// 手で時間を変えたら、プリセットの音はもう使わない
     pub fn push_digit(&mut self, digit: u8, cx: &mut Context<Self>) {
         if self.status != TimerStatus::Idle {
             return;
         }
+        self.finish_sound = None;
         self.time_digits = self.time_digits * 10 + digit as u32;
         self.time_digits %= 1000000;
         self.notify_digits_display(cx);
     }
 
+    /// プリセットの時間を、手で数字を打つのと同じ push_digit で入れ直す
+    pub fn load_preset(&mut self, preset: &Preset, cx: &mut Context<Self>) {
+        if self.status != TimerStatus::Idle {
+            self.reset(cx);
+        }
+        self.time_digits = 0;
+        for digit in preset.digits() {
+            self.push_digit(digit, cx);
+        }
+        self.finish_sound = preset.sound.clone();
+    }
+
     pub fn pop_digit(&mut self, cx: &mut Context<Self>) {
         if self.status != TimerStatus::Idle {
             return;
         }
+        self.finish_sound = None;
         self.time_digits /= 10;
         self.notify_digits_display(cx);
     }
@@ -139,12 +163,16 @@ impl TimerModel {
         }
     }
 
-    pub fn play_finish_sound() {
+    pub fn play_finish_sound(sound: Option<PathBuf>) {
         use rodio::{Decoder, OutputStream, Sink, source::*};
         use std::io::Cursor;
 
-        let sound_data = include_bytes!("../assets/finish_sound.mp3");
+        let bundled: &[u8] = include_bytes!("../assets/finish_sound.mp3");
         std::thread::spawn(move || {
+            // プリセットの音が読めなければ同梱の音にする
+            let sound_data = sound
+                .and_then(|path| std::fs::read(path).ok())
+                .map_or(std::borrow::Cow::Borrowed(bundled), std::borrow::Cow::Owned);
             let Ok((_stream, stream_handle)) = OutputStream::try_default() else {
                 return;
             };
@@ -216,7 +244,7 @@ impl TimerModel {
 
                                     if remaining <= 0.0 {
                                         this.status = TimerStatus::Finished;
-                                        TimerModel::play_finish_sound();
+                                        TimerModel::play_finish_sound(this.finish_sound.clone());
                                         this._timer_task = None;
                                         model_cx.notify();
                                         return true;
@@ -0,0 +1,258 @@
+use crate::storage;
+use crate::view::Mode;
+use gpui::{AsyncApp, Context, PathPromptOptions, Task, WeakEntity};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// ワンクリックで呼び出せる時間の設定
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct Preset {
+    pub name: String,
+    pub secs: u32,
+    /// タイマーが終わったときに鳴らす音。なければ同梱の音
+    #[serde(default)]
+    pub sound: Option<PathBuf>,
+    /// 選んだときに開くモード
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+impl Preset {
+    pub fn new(name: &str, secs: u32) -> Self {
+        Preset {
+            name: name.to_string(),
+            secs,
+            sound: None,
+            mode: Mode::Timer,
+        }
+    }
+
+    /// push_digit に渡す HHMMSS の数字列
+    pub fn digits(&self) -> Vec<u8> {
+        let (hours, minutes, seconds) = (
+            (self.secs / 3600).min(99),
+            self.secs % 3600 / 60,
+            self.secs % 60,
+        );
+        format!("{:02}{:02}{:02}", hours, minutes, seconds)
+            .bytes()
+            .map(|b| b - b'0')
+            .collect()
+    }
+}
+
+pub struct PresetModel {
+    pub presets: Vec<Preset>,
+    pub edit_mode: bool,
+    pub editing: Option<usize>,
+    /// 編集中のプリセットを消してよいか確かめている
+    pub confirm_remove: bool,
+    pub message: String,
+    _pick_task: Option<Task<()>>,
+}
+
+impl PresetModel {
+    pub fn new() -> Self {
+        let (presets, message) = match Self::load() {
+            Ok(Some(presets)) => (presets, String::new()),
+            Ok(None) => (Self::default_presets(), String::new()),
+            Err(err) => (
+                Self::default_presets(),
+                format!("プリセットの読み込みに失敗: {}", err),
+            ),
+        };
+        PresetModel {
+            presets,
+            edit_mode: false,
+            editing: None,
+            confirm_remove: false,
+            message,
+            _pick_task: None,
+        }
+    }
+
+    fn default_presets() -> Vec<Preset> {
+        vec![
+            Preset::new("Tea", 3 * 60),
+            Preset::new("Pomodoro", 25 * 60),
+            Preset::new("Break", 5 * 60),
+        ]
+    }
+
+    /// $XDG_CONFIG_HOME/stim/presets.json (なければ ~/.config/stim/presets.json)
+    pub fn config_path() -> Option<PathBuf> {
+        storage::xdg_path("XDG_CONFIG_HOME", ".config", "presets.json")
+    }
+
+    fn load() -> Result<Option<Vec<Preset>>, String> {
+        let Some(path) = Self::config_path() else {
+            return Ok(None);
+        };
+        storage::load_json(&path)
+    }
+
+    fn save(&mut self) {
+        let Some(path) = Self::config_path() else {
+            return;
+        };
+        if let Err(err) = storage::save_json(&path, &self.presets) {
+            self.message = format!("プリセットの保存に失敗: {}", err);
+        }
+    }
+
+    pub fn toggle_edit_mode(&mut self, cx: &mut Context<Self>) {
+        self.finish_editing();
+        self.edit_mode = !self.edit_mode;
+        cx.notify();
+    }
+
+    pub fn select_for_edit(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index < self.presets.len() && self.editing != Some(index) {
+            self.finish_editing();
+            self.editing = Some(index);
+            cx.notify();
+        }
+    }
+
+    /// 名前はキーを打つたびではなく、編集を終えたときにまとめて保存する
+    pub fn commit_name(&mut self, cx: &mut Context<Self>) {
+        if self.editing.is_some() {
+            self.save();
+            cx.notify();
+        }
+    }
+
+    fn finish_editing(&mut self) {
+        if self.editing.take().is_some() {
+            self.save();
+        }
+        self.confirm_remove = false;
+    }
+
+    pub fn add(&mut self, secs: u32, cx: &mut Context<Self>) {
+        if secs == 0 {
+            return;
+        }
+        let name = format!("{}:{:02}", secs / 60, secs % 60);
+        self.confirm_remove = false;
+        self.presets.push(Preset::new(&name, secs));
+        self.editing = Some(self.presets.len() - 1);
+        self.save();
+        cx.notify();
+    }
+
+    /// 削除は確認を挟む。1回目は確認を出すだけ
+    pub fn request_remove(&mut self, cx: &mut Context<Self>) {
+        if self.editing.is_some() {
+            self.confirm_remove = true;
+            cx.notify();
+        }
+    }
+
+    pub fn cancel_remove(&mut self, cx: &mut Context<Self>) {
+        self.confirm_remove = false;
+        cx.notify();
+    }
+
+    pub fn remove_editing(&mut self, cx: &mut Context<Self>) {
+        let Some(index) = self.editing.filter(|index| *index < self.presets.len()) else {
+            return;
+        };
+        if !self.confirm_remove {
+            return;
+        }
+        self.presets.remove(index);
+        self.editing = None;
+        self.confirm_remove = false;
+        self.save();
+        cx.notify();
+    }
+
+    pub fn push_name_char(&mut self, c: char, cx: &mut Context<Self>) {
+        if let Some(preset) = self.editing.and_then(|index| self.presets.get_mut(index)) {
+            preset.name.push(c);
+            cx.notify();
+        }
+    }
+
+    pub fn pop_name_char(&mut self, cx: &mut Context<Self>) {
+        if let Some(preset) = self.editing.and_then(|index| self.presets.get_mut(index)) {
+            preset.name.pop();
+            cx.notify();
+        }
+    }
+
+    pub fn set_editing_secs(&mut self, secs: u32, cx: &mut Context<Self>) {
+        if secs == 0 {
+            return;
+        }
+        if let Some(preset) = self.editing.and_then(|index| self.presets.get_mut(index)) {
+            preset.secs = secs;
+            self.save();
+            cx.notify();
+        }
+    }
+
+    pub fn cycle_editing_mode(&mut self, cx: &mut Context<Self>) {
+        if let Some(preset) = self.editing.and_then(|index| self.presets.get_mut(index)) {
+            preset.mode = match preset.mode {
+                Mode::Timer => Mode::Metronome,
+                Mode::Metronome => Mode::Breathing,
+                Mode::Breathing | Mode::Clock => Mode::Timer,
+            };
+            self.save();
+            cx.notify();
+        }
+    }
+
+    /// ファイル選択ダイアログで音を選ぶ。選んでいる間に別のプリセットに移っても、
+    /// 開いたときのプリセットに入れる
+    pub fn pick_sound(&mut self, cx: &mut Context<Self>) {
+        let Some(index) = self.editing else {
+            return;
+        };
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("終わりの音".into()),
+        });
+        self._pick_task = Some(cx.spawn(
+            move |we: WeakEntity<PresetModel>, cx_ref: &mut AsyncApp| {
+                let mut cx = cx_ref.clone();
+                async move {
+                    let picked = paths.await;
+                    let _ = we.update(&mut cx, |this, model_cx| {
+                        this._pick_task = None;
+                        match picked {
+                            Ok(Ok(Some(paths))) => {
+                                if let Some(path) = paths.into_iter().next() {
+                                    this.set_sound(index, Some(path), model_cx);
+                                }
+                            }
+                            Ok(Err(err)) => {
+                                this.message = format!("音のファイルを選べない: {}", err);
+                                model_cx.notify();
+                            }
+                            _ => {}
+                        }
+                    });
+                }
+            },
+        ));
+    }
+
+    pub fn clear_sound(&mut self, cx: &mut Context<Self>) {
+        if let Some(index) = self.editing {
+            self.set_sound(index, None, cx);
+        }
+    }
+
+    fn set_sound(&mut self, index: usize, sound: Option<PathBuf>, cx: &mut Context<Self>) {
+        if let Some(preset) = self.presets.get_mut(index) {
+            preset.sound = sound;
+            self.save();
+            cx.notify();
+        }
+    }
+}
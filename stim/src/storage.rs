@@ -0,0 +1,33 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+
+/// $`env`/stim/`file` (なければ ~/`fallback`/stim/`file`)
+pub fn xdg_path(env: &str, fallback: &str, file: &str) -> Option<PathBuf> {
+    let dir = std::env::var_os(env)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))?;
+    Some(dir.join("stim").join(file))
+}
+
+/// ファイルがまだなければ Ok(None)
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    };
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|err| err.to_string())
+}
+
+/// 親のディレクトリを作ってから整形したJSONで書く
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(|err| err.to_string())?;
+    let json = serde_json::to_string_pretty(value).map_err(|err| err.to_string())?;
+    std::fs::write(path, json).map_err(|err| err.to_string())
+}
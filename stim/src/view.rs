@@ -1,24 +1,120 @@
+use crate::breathing::{BreathPhase, BreathingModel, BreathingStatus, SessionLength};
+use crate::clock::WorldClockModel;
+use crate::metronome::{MetronomeModel, MetronomeStatus};
+use crate::mini_view::MiniTimerView;
 use crate::model::{TimerModel, TimerStatus};
+use crate::preset::PresetModel;
+use chrono::Utc;
 use gpui::{
-    App, Canvas, Context, Entity, FocusHandle, Half, IntoElement, KeyDownEvent, MouseButton,
-    PathBuilder, Render, Window, canvas, div, point, prelude::*, px, rgb,
+    App, Bounds, Canvas, Context, Entity, FocusHandle, Half, IntoElement, KeyDownEvent,
+    MouseButton, PathBuilder, Pixels, Render, Window, canvas, div, fill, point, prelude::*, px,
+    rgb, size,
 };
+use serde::{Deserialize, Serialize};
 use std::f32::consts::{FRAC_PI_2, PI};
 
+// 輪の大きさを決めるときに差し引く、輪以外の要素の高さ
+/// p_5 の上下の余白
+const PADDING_HEIGHT: f32 = 40.;
+/// gap_3 の縦の間隔
+const ROW_GAP: f32 = 12.;
+const TABS_HEIGHT: f32 = 24.;
+const BUTTON_HEIGHT: f32 = 40.;
+/// プリセットや呼吸の設定に使う小さいボタンの行と、その行どうしの間隔
+const CHIP_ROW_HEIGHT: f32 = 24.;
+const CHIP_ROW_GAP: f32 = 5.;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Mode {
+    #[default]
+    Timer,
+    Metronome,
+    Breathing,
+    Clock,
+}
+
+impl Mode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Mode::Timer => "Timer",
+            Mode::Metronome => "Metronome",
+            Mode::Breathing => "Breathe",
+            Mode::Clock => "Clock",
+        }
+    }
+}
+
 pub struct TimerView {
     timer_ticket: Entity<TimerModel>,
+    metronome_ticket: Entity<MetronomeModel>,
+    breathing_ticket: Entity<BreathingModel>,
+    clock_ticket: Entity<WorldClockModel>,
+    preset_ticket: Entity<PresetModel>,
+    mode: Mode,
     focus_handle: FocusHandle,
 }
 
 impl TimerView {
     pub fn new(cx: &mut Context<Self>) -> Self {
         let timer_ticket = cx.new(|_| TimerModel::new());
+        let metronome_ticket = cx.new(|_| MetronomeModel::new());
+        let breathing_ticket = cx.new(|_| BreathingModel::new());
+        let clock_ticket = cx.new(|_| WorldClockModel::new());
+        let preset_ticket = cx.new(|_| PresetModel::new());
         Self {
             timer_ticket,
+            metronome_ticket,
+            breathing_ticket,
+            clock_ticket,
+            preset_ticket,
+            mode: Mode::Timer,
             focus_handle: cx.focus_handle(),
         }
     }
 
+    fn switch_mode(&mut self, mode: Mode, cx: &mut Context<Self>) {
+        if mode != Mode::Metronome {
+            self.metronome_ticket
+                .update(cx, |metronome_model, cx| metronome_model.stop(cx));
+        }
+        if mode != Mode::Breathing {
+            self.breathing_ticket
+                .update(cx, |breathing_model, cx| breathing_model.stop(cx));
+        }
+        self.clock_ticket.update(cx, |clock_model, cx| {
+            clock_model.set_active(mode == Mode::Clock, cx)
+        });
+        self.mode = mode;
+        cx.notify();
+    }
+
+    fn mode_tabs_element(current_mode: Mode, view_ticket: Entity<TimerView>) -> impl IntoElement {
+        div().flex().flex_row().w_full().gap(px(5.)).children(
+            [Mode::Timer, Mode::Metronome, Mode::Breathing, Mode::Clock].map(|mode| {
+                let view_ticket = view_ticket.clone();
+                let (bg, text) = if mode == current_mode {
+                    (rgb(0x4a5c4a), rgb(0xd6e0d6))
+                } else {
+                    (rgb(0xb5c4b5), rgb(0x1b2635))
+                };
+                div()
+                    .flex()
+                    .justify_center()
+                    .items_center()
+                    .rounded(px(10.0))
+                    .h(px(24.))
+                    .w_full()
+                    .text_size(px(12.))
+                    .bg(bg)
+                    .text_color(text)
+                    .child(mode.label())
+                    .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                        view_ticket.update(app_cx, |view, cx| view.switch_mode(mode, cx))
+                    })
+            }),
+        )
+    }
+
     fn toggle_button_element(
         &self,
         status: &TimerStatus,
@@ -86,7 +182,16 @@ impl TimerView {
         )
     }
 
-    fn time_display_element(time: &TimerModel) -> impl IntoElement {
+    /// ウィンドウの大きさに合わせた輪の直径。
+    /// reserved_height は輪以外の要素が使う高さ
+    fn ring_diameter(window: &Window, reserved_height: f32) -> Pixels {
+        let viewport = window.viewport_size();
+        let width = viewport.width - px(40.);
+        let height = viewport.height - px(reserved_height);
+        width.min(height).max(px(80.))
+    }
+
+    fn time_display_element(time: &TimerModel, text_size: Pixels) -> impl IntoElement {
         let label = if time.status == TimerStatus::Running || time.status == TimerStatus::Finished {
             if time.display_hours > 0 {
                 format!(
@@ -109,7 +214,7 @@ impl TimerView {
             .flex()
             .items_center()
             .justify_center()
-            .text_size(px(40.0))
+            .text_size(text_size)
             .text_center()
             .text_color(rgb(0x1b2635))
             .child(label)
@@ -172,12 +277,39 @@ impl TimerView {
     }
 
     fn key_handler(
+        view_ticket: Entity<TimerView>,
         timer_ticket: Entity<TimerModel>,
+        preset_ticket: Entity<PresetModel>,
     ) -> impl Fn(&KeyDownEvent, &mut Window, &mut App) {
-        move |event, _window, app| {
+        move |event, window, app| {
+            if Self::preset_shortcut(&view_ticket, event, app) {
+                return;
+            }
             let key = event.keystroke.key.as_str();
-            println!("Key pressed: {}", key);
-            if ("0"..="9").contains(&key) {
+            // 編集中のプリセットがあれば、文字はその名前の入力にする
+            if preset_ticket.read(app).editing.is_some() {
+                let typed = event
+                    .keystroke
+                    .key_char
+                    .as_deref()
+                    .and_then(|key_char| key_char.chars().next())
+                    .filter(|c| c.is_alphanumeric() || *c == ' ');
+                if let Some(c) = typed {
+                    preset_ticket.update(app, |model, cx| model.push_name_char(c, cx));
+                    return;
+                }
+                if key == "backspace" {
+                    preset_ticket.update(app, |model, cx| model.pop_name_char(cx));
+                    return;
+                }
+                if key == "enter" {
+                    preset_ticket.update(app, |model, cx| model.commit_name(cx));
+                    return;
+                }
+            }
+            if key == "m" {
+                MiniTimerView::open(timer_ticket.clone(), window, app);
+            } else if ("0"..="9").contains(&key) {
                 timer_ticket.update(app, |model, cx| {
                     if let Ok(digit) = key.parse::<u8>() {
                         model.push_digit(digit, cx);
@@ -197,12 +329,660 @@ impl TimerView {
             }
         }
     }
-}
 
-impl Render for TimerView {
-    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn bpm_display_element(metronome: &MetronomeModel) -> impl IntoElement {
+        let (label, color) = match metronome.bpm_digits {
+            Some(digits) => (digits.to_string(), rgb(0x4a5c4a)),
+            None => (metronome.bpm.to_string(), rgb(0x1b2635)),
+        };
+        div()
+            .flex()
+            .flex_row()
+            .items_end()
+            .justify_center()
+            .gap(px(5.))
+            .child(div().text_size(px(40.0)).text_color(color).child(label))
+            .child(
+                div()
+                    .text_size(px(14.0))
+                    .text_color(rgb(0x4a5c4a))
+                    .pb(px(8.))
+                    .child("BPM"),
+            )
+    }
+
+    fn signature_element(
+        metronome: &MetronomeModel,
+        metronome_ticket: Entity<MetronomeModel>,
+    ) -> impl IntoElement {
+        let signature = metronome.signature();
+        div()
+            .flex()
+            .justify_center()
+            .items_center()
+            .rounded(px(10.0))
+            .h(px(24.))
+            .px(px(12.))
+            .bg(rgb(0xb5c4b5))
+            .text_color(rgb(0x1b2635))
+            .child(format!("{}/{}", signature.beats, signature.unit))
+            .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                metronome_ticket.update(app_cx, |metronome_model, cx| {
+                    metronome_model.cycle_signature(cx)
+                })
+            })
+    }
+
+    fn beat_indicator_element(beats_per_bar: u32, current_beat: Option<(u32, f32)>) -> Canvas<()> {
+        canvas(
+            |_, _, _| {},
+            move |bounds, _, window, _app| {
+                let slot_width = bounds.size.width / beats_per_bar as f32;
+                let base_radius = bounds.size.height.half().min(slot_width.half()) * 0.6;
+                for beat in 0..beats_per_bar {
+                    let center = point(
+                        bounds.origin.x + slot_width * (beat as f32 + 0.5),
+                        bounds.center().y,
+                    );
+                    let (radius, color) = match current_beat {
+                        // 拍の頭で大きく、拍が進むにつれて元の大きさに戻す
+                        Some((active, phase)) if active == beat => {
+                            let color = if beat == 0 {
+                                rgb(0xd76a1d)
+                            } else {
+                                rgb(0x4a5c4a)
+                            };
+                            (base_radius * (1.0 + 0.4 * (1.0 - phase)), color)
+                        }
+                        _ => (base_radius, rgb(0xb5c4b5)),
+                    };
+                    let dot = Bounds::centered_at(center, size(radius * 2.0, radius * 2.0));
+                    window.paint_quad(fill(dot, color).corner_radii(radius));
+                }
+            },
+        )
+        .w_full()
+        .h(px(40.))
+    }
+
+    fn metronome_buttons_element(
+        status: MetronomeStatus,
+        metronome_ticket: Entity<MetronomeModel>,
+    ) -> impl IntoElement {
+        let label = match status {
+            MetronomeStatus::Stopped => "▶︎",
+            MetronomeStatus::Playing => "■",
+        };
+        let button = || {
+            div()
+                .flex()
+                .justify_center()
+                .items_center()
+                .rounded(px(20.0))
+                .h(px(40.))
+                .w_full()
+                .bg(rgb(0x4a5c4a))
+                .text_color(rgb(0xd6e0d6))
+        };
+        div()
+            .flex()
+            .flex_row()
+            .w_full()
+            .gap(px(5.))
+            .child(button().child(label).on_mouse_down(MouseButton::Left, {
+                let metronome_ticket = metronome_ticket.clone();
+                move |_event, _window, app_cx| {
+                    metronome_ticket
+                        .update(app_cx, |metronome_model, cx| metronome_model.toggle(cx))
+                }
+            }))
+            .child(button().child("Tap").on_mouse_down(
+                MouseButton::Left,
+                move |_event, _window, app_cx| {
+                    metronome_ticket.update(app_cx, |metronome_model, cx| metronome_model.tap(cx))
+                },
+            ))
+    }
+
+    fn metronome_key_handler(
+        view_ticket: Entity<TimerView>,
+        metronome_ticket: Entity<MetronomeModel>,
+    ) -> impl Fn(&KeyDownEvent, &mut Window, &mut App) {
+        move |event, _window, app| {
+            if Self::preset_shortcut(&view_ticket, event, app) {
+                return;
+            }
+            let key = event.keystroke.key.as_str();
+            metronome_ticket.update(app, |model, cx| {
+                if ("0"..="9").contains(&key) {
+                    if let Ok(digit) = key.parse::<u8>() {
+                        model.push_digit(digit, cx);
+                    }
+                } else if key == "backspace" {
+                    model.pop_digit(cx);
+                } else if key == "enter" {
+                    if model.bpm_digits.is_some() {
+                        model.commit_bpm(cx);
+                    } else {
+                        model.toggle(cx);
+                    }
+                } else if key == "t" || key == "space" {
+                    model.tap(cx);
+                } else if key == "up" {
+                    model.set_bpm(model.bpm + 1, cx);
+                } else if key == "down" {
+                    model.set_bpm(model.bpm.saturating_sub(1), cx);
+                }
+            })
+        }
+    }
+
+    fn breathing_settings_element(
+        breathing: &BreathingModel,
+        breathing_ticket: Entity<BreathingModel>,
+    ) -> impl IntoElement {
+        let chip = |label: String| {
+            div()
+                .flex()
+                .justify_center()
+                .items_center()
+                .rounded(px(10.0))
+                .h(px(24.))
+                .px(px(8.))
+                .text_size(px(12.))
+                .bg(rgb(0xb5c4b5))
+                .text_color(rgb(0x1b2635))
+                .child(label)
+        };
+        let length_label = match breathing.session_length() {
+            SessionLength::Cycles(cycles) => format!("{} cycles", cycles),
+            SessionLength::Minutes(minutes) => format!("{} min", minutes),
+        };
+        let sound_label = if breathing.sound_enabled { "♪" } else { "-" };
+
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap(px(5.))
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap(px(5.))
+                    .child(chip(breathing.pattern.name.to_string()).on_mouse_down(
+                        MouseButton::Left,
+                        {
+                            let breathing_ticket = breathing_ticket.clone();
+                            move |_event, _window, app_cx| {
+                                breathing_ticket.update(app_cx, |breathing_model, cx| {
+                                    breathing_model.cycle_pattern(cx)
+                                })
+                            }
+                        },
+                    ))
+                    .child(chip(length_label).on_mouse_down(MouseButton::Left, {
+                        let breathing_ticket = breathing_ticket.clone();
+                        move |_event, _window, app_cx| {
+                            breathing_ticket.update(app_cx, |breathing_model, cx| {
+                                breathing_model.cycle_session_length(cx)
+                            })
+                        }
+                    }))
+                    .child(
+                        chip(sound_label.to_string()).on_mouse_down(MouseButton::Left, {
+                            let breathing_ticket = breathing_ticket.clone();
+                            move |_event, _window, app_cx| {
+                                breathing_ticket.update(app_cx, |breathing_model, cx| {
+                                    breathing_model.toggle_sound(cx)
+                                })
+                            }
+                        }),
+                    ),
+            )
+            .child(div().flex().flex_row().gap(px(5.)).children(
+                // 吸う・止める・吐く・止めるの秒数。クリックで1秒ずつ増やす
+                BreathPhase::ALL.map(|phase| {
+                    let breathing_ticket = breathing_ticket.clone();
+                    chip(format!("{}", breathing.pattern.secs[phase as usize])).on_mouse_down(
+                        MouseButton::Left,
+                        move |_event, _window, app_cx| {
+                            breathing_ticket.update(app_cx, |breathing_model, cx| {
+                                breathing_model.bump_phase_secs(phase, cx)
+                            })
+                        },
+                    )
+                }),
+            ))
+    }
+
+    fn breathing_ring_element(breathing: &BreathingModel, ring_size: Pixels) -> impl IntoElement {
+        let (label, progress) = match breathing.current_phase() {
+            Some((cycle, phase, progress)) => (
+                format!(
+                    "{}\n{}/{}",
+                    phase.label(),
+                    cycle + 1,
+                    breathing.total_cycles()
+                ),
+                progress.max(0.01),
+            ),
+            None if breathing.status == BreathingStatus::Finished => ("おわり".to_string(), 1.0),
+            None => ("Ready".to_string(), 1.0),
+        };
+        let diameter = ring_size * breathing.ring_scale();
+
+        div()
+            .relative()
+            .flex()
+            .justify_center()
+            .items_center()
+            .size(ring_size)
+            .child(
+                div()
+                    .size(diameter)
+                    .child(Self::progress_circle_element(progress)),
+            )
+            .child(
+                div()
+                    .absolute()
+                    .inset_0()
+                    .flex()
+                    .justify_center()
+                    .items_center()
+                    .text_size(ring_size * 0.12)
+                    .text_center()
+                    .text_color(rgb(0x1b2635))
+                    .child(label),
+            )
+    }
+
+    fn breathing_key_handler(
+        view_ticket: Entity<TimerView>,
+        breathing_ticket: Entity<BreathingModel>,
+    ) -> impl Fn(&KeyDownEvent, &mut Window, &mut App) {
+        move |event, _window, app| {
+            if Self::preset_shortcut(&view_ticket, event, app) {
+                return;
+            }
+            let key = event.keystroke.key.as_str();
+            if key == "enter" {
+                breathing_ticket.update(app, |model, cx| model.toggle(cx));
+            }
+        }
+    }
+
+    fn format_offset(offset_secs: i32) -> String {
+        if offset_secs == 0 {
+            return "±0".to_string();
+        }
+        let sign = if offset_secs > 0 { "+" } else { "-" };
+        let minutes = offset_secs.abs() / 60;
+        if minutes % 60 == 0 {
+            format!("{}{}h", sign, minutes / 60)
+        } else {
+            format!("{}{}:{:02}h", sign, minutes / 60, minutes % 60)
+        }
+    }
+
+    fn zone_rows_element(
+        clock: &WorldClockModel,
+        clock_ticket: Entity<WorldClockModel>,
+    ) -> impl IntoElement {
+        let now = Utc::now();
+        div()
+            .flex()
+            .flex_col()
+            .w_full()
+            .gap(px(2.))
+            .children(clock.zones.iter().enumerate().map(|(index, &zone)| {
+                let local = now.with_timezone(&zone);
+                let day_night = if WorldClockModel::is_daytime(zone, now) {
+                    "☀"
+                } else {
+                    "☾"
+                };
+                let city = zone.name().rsplit('/').next().unwrap_or(zone.name());
+                let bg = if index == clock.selected {
+                    rgb(0xb5c4b5)
+                } else {
+                    rgb(0xd6e0d6)
+                };
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(px(5.))
+                    .px(px(6.))
+                    .rounded(px(6.))
+                    .bg(bg)
+                    .text_size(px(12.))
+                    .text_color(rgb(0x1b2635))
+                    .child(div().w(px(14.)).child(day_night))
+                    .child(div().flex_1().child(city.replace('_', " ")))
+                    .child(
+                        div()
+                            .text_size(px(16.))
+                            .child(local.format("%H:%M:%S").to_string()),
+                    )
+                    .child(
+                        div()
+                            .w(px(44.))
+                            .text_color(rgb(0x4a5c4a))
+                            .child(Self::format_offset(WorldClockModel::offset_from_local(
+                                zone, now,
+                            ))),
+                    )
+                    .child(div().text_color(rgb(0x4a5c4a)).child("×").on_mouse_down(
+                        MouseButton::Left,
+                        {
+                            let clock_ticket = clock_ticket.clone();
+                            move |_event, _window, app_cx| {
+                                app_cx.stop_propagation();
+                                clock_ticket.update(app_cx, |clock_model, cx| {
+                                    clock_model.remove_zone(index, cx)
+                                })
+                            }
+                        },
+                    ))
+                    .on_mouse_down(MouseButton::Left, {
+                        let clock_ticket = clock_ticket.clone();
+                        move |_event, _window, app_cx| {
+                            clock_ticket.update(app_cx, |clock_model, cx| {
+                                clock_model.select_zone(index, cx)
+                            })
+                        }
+                    })
+            }))
+    }
+
+    fn clock_input_element(clock: &WorldClockModel) -> impl IntoElement {
+        let zone_label = if clock.zone_query.is_empty() {
+            "+ zone".to_string()
+        } else {
+            format!("+ {}_", clock.zone_query)
+        };
+        let target_label = match (clock.target, clock.target_remaining_secs()) {
+            (Some(target), Some(remaining)) => format!(
+                "{:02}:{:02} {} まで {}:{:02}:{:02}",
+                target.hour,
+                target.minute,
+                target
+                    .zone
+                    .name()
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(target.zone.name()),
+                remaining / 3600,
+                remaining % 3600 / 60,
+                remaining % 60
+            ),
+            _ => format!(
+                "→ {:02}:{:02}",
+                clock.target_digits / 100,
+                clock.target_digits % 100
+            ),
+        };
+        div()
+            .flex()
+            .flex_col()
+            .w_full()
+            .gap(px(2.))
+            .text_size(px(12.))
+            .text_color(rgb(0x4a5c4a))
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .justify_between()
+                    .child(zone_label)
+                    .child(target_label),
+            )
+            .child(div().text_color(rgb(0xd76a1d)).child(clock.message.clone()))
+    }
+
+    fn clock_key_handler(
+        view_ticket: Entity<TimerView>,
+        clock_ticket: Entity<WorldClockModel>,
+    ) -> impl Fn(&KeyDownEvent, &mut Window, &mut App) {
+        move |event, _window, app| {
+            if Self::preset_shortcut(&view_ticket, event, app) {
+                return;
+            }
+            let key = event.keystroke.key.as_str();
+            // 文字はゾーン名、数字は目標時刻(HHMM)の入力にする
+            let typed = event
+                .keystroke
+                .key_char
+                .as_deref()
+                .and_then(|key_char| key_char.chars().next())
+                .filter(|c| c.is_ascii_alphabetic() || " /_-+".contains(*c));
+            clock_ticket.update(app, |model, cx| {
+                if ("0"..="9").contains(&key) {
+                    if let Ok(digit) = key.parse::<u8>() {
+                        model.push_digit(digit, cx);
+                    }
+                } else if let Some(c) = typed {
+                    model.push_query_char(c, cx);
+                } else if key == "backspace" {
+                    model.pop_input(cx);
+                } else if key == "enter" {
+                    if !model.zone_query.is_empty() {
+                        model.add_zone(cx);
+                    } else {
+                        model.set_target(cx);
+                    }
+                } else if key == "escape" {
+                    model.clear_target(cx);
+                }
+            })
+        }
+    }
+
+    /// Ctrl(macOSはCmd)+数字でプリセットを選ぶ。どのモードからでも効く。
+    /// 修飾キー付きのキーはここで受け取ったことにして、各モードの入力には回さない
+    fn preset_shortcut(
+        view_ticket: &Entity<TimerView>,
+        event: &KeyDownEvent,
+        app: &mut App,
+    ) -> bool {
+        if !event.keystroke.modifiers.secondary() {
+            return false;
+        }
+        if let Ok(number @ 1..=9) = event.keystroke.key.parse::<usize>() {
+            view_ticket.update(app, |view, cx| view.load_preset(number - 1, cx));
+        }
+        true
+    }
+
+    /// プリセットのモードに切り替えて、その時間を入れる
+    fn load_preset(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(preset) = self.preset_ticket.read(cx).presets.get(index).cloned() else {
+            return;
+        };
+        self.switch_mode(preset.mode, cx);
+        match preset.mode {
+            Mode::Timer => self
+                .timer_ticket
+                .update(cx, |timer_model, cx| timer_model.load_preset(&preset, cx)),
+            Mode::Breathing => self.breathing_ticket.update(cx, |breathing_model, cx| {
+                breathing_model.set_session_secs(preset.secs, cx)
+            }),
+            Mode::Metronome | Mode::Clock => {}
+        }
+    }
+
+    fn presets_bar_element(
+        presets: &PresetModel,
+        view_ticket: Entity<TimerView>,
+        preset_ticket: Entity<PresetModel>,
+        timer_ticket: Entity<TimerModel>,
+    ) -> impl IntoElement {
+        let chip = |label: String, selected: bool| {
+            div()
+                .flex()
+                .justify_center()
+                .items_center()
+                .rounded(px(10.0))
+                .h(px(24.))
+                .px(px(8.))
+                .text_size(px(12.))
+                .bg(if selected {
+                    rgb(0x4a5c4a)
+                } else {
+                    rgb(0xb5c4b5)
+                })
+                .text_color(if selected {
+                    rgb(0xd6e0d6)
+                } else {
+                    rgb(0x1b2635)
+                })
+                .child(label)
+        };
+        let edit_mode = presets.edit_mode;
+
+        div()
+            .flex()
+            .flex_col()
+            .w_full()
+            .gap(px(5.))
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_wrap()
+                    .justify_center()
+                    .gap(px(5.))
+                    .children(presets.presets.iter().enumerate().map(|(index, preset)| {
+                        let preset_ticket = preset_ticket.clone();
+                        let view_ticket = view_ticket.clone();
+                        chip(preset.name.clone(), presets.editing == Some(index)).on_mouse_down(
+                            MouseButton::Left,
+                            move |_event, _window, app_cx| {
+                                if preset_ticket.read(app_cx).edit_mode {
+                                    preset_ticket.update(app_cx, |model, cx| {
+                                        model.select_for_edit(index, cx)
+                                    });
+                                } else {
+                                    view_ticket
+                                        .update(app_cx, |view, cx| view.load_preset(index, cx));
+                                }
+                            },
+                        )
+                    }))
+                    .child(
+                        chip("+".to_string(), false).on_mouse_down(MouseButton::Left, {
+                            let preset_ticket = preset_ticket.clone();
+                            let timer_ticket = timer_ticket.clone();
+                            move |_event, _window, app_cx| {
+                                let secs = timer_ticket.update(app_cx, |timer_model, _| {
+                                    timer_model.culc_hms_secs() as u32
+                                });
+                                preset_ticket.update(app_cx, |model, cx| model.add(secs, cx));
+                            }
+                        }),
+                    )
+                    .child(
+                        chip("✎".to_string(), edit_mode).on_mouse_down(MouseButton::Left, {
+                            let preset_ticket = preset_ticket.clone();
+                            move |_event, _window, app_cx| {
+                                preset_ticket
+                                    .update(app_cx, |model, cx| model.toggle_edit_mode(cx));
+                            }
+                        }),
+                    ),
+            )
+            .when_some(
+                presets.editing.and_then(|index| presets.presets.get(index)),
+                |this, preset| {
+                    let action =
+                        |label: String, act: fn(&mut PresetModel, &mut Context<PresetModel>)| {
+                            let preset_ticket = preset_ticket.clone();
+                            chip(label, false)
+                                .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                                    preset_ticket.update(app_cx, act)
+                                })
+                        };
+                    if presets.confirm_remove {
+                        return this.child(
+                            div()
+                                .flex()
+                                .flex_row()
+                                .justify_center()
+                                .gap(px(5.))
+                                .child(chip(format!("「{}」を削除？", preset.name), true))
+                                .child(action("削除".to_string(), PresetModel::remove_editing))
+                                .child(action("やめる".to_string(), PresetModel::cancel_remove)),
+                        );
+                    }
+                    let sound_label = match &preset.sound {
+                        Some(path) => format!(
+                            "♪ {}",
+                            path.file_stem().unwrap_or_default().to_string_lossy()
+                        ),
+                        None => "♪ -".to_string(),
+                    };
+                    this.child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .flex_wrap()
+                            .justify_center()
+                            .gap(px(5.))
+                            .child(chip(
+                                format!("{}:{:02}", preset.secs / 60, preset.secs % 60),
+                                false,
+                            ))
+                            .child(
+                                chip("⤓".to_string(), false).on_mouse_down(MouseButton::Left, {
+                                    let preset_ticket = preset_ticket.clone();
+                                    move |_event, _window, app_cx| {
+                                        // 今タイマーに入っている時間で上書きする
+                                        let secs = timer_ticket.update(app_cx, |timer_model, _| {
+                                            timer_model.culc_hms_secs() as u32
+                                        });
+                                        preset_ticket.update(app_cx, |model, cx| {
+                                            model.set_editing_secs(secs, cx)
+                                        });
+                                    }
+                                }),
+                            )
+                            .child(action(
+                                preset.mode.label().to_string(),
+                                PresetModel::cycle_editing_mode,
+                            ))
+                            .child(action(sound_label, PresetModel::pick_sound))
+                            .when(preset.sound.is_some(), |this| {
+                                this.child(action("×".to_string(), PresetModel::clear_sound))
+                            })
+                            .child(action("削除".to_string(), PresetModel::request_remove)),
+                    )
+                },
+            )
+            .when(!presets.message.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_size(px(12.))
+                        .text_color(rgb(0xd76a1d))
+                        .child(presets.message.clone()),
+                )
+            })
+    }
+
+    fn timer_render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let timer_ticket = self.timer_ticket.clone();
+        let mode_tabs = Self::mode_tabs_element(self.mode, cx.entity());
+        let view_ticket = cx.entity();
+        let preset_ticket = self.preset_ticket.clone();
+        let presets = self.preset_ticket.read(cx);
         let timer_model = self.timer_ticket.read(cx);
+        // タブ、プリセット、ボタン、余白と間隔の分を除いた大きさで輪を描く
+        let mut reserved_height =
+            PADDING_HEIGHT + TABS_HEIGHT + CHIP_ROW_HEIGHT + BUTTON_HEIGHT + ROW_GAP * 3.;
+        if presets.editing.is_some() {
+            reserved_height += CHIP_ROW_GAP + CHIP_ROW_HEIGHT;
+        }
+        let diameter = Self::ring_diameter(window, reserved_height);
 
         let background_color = match timer_model.status {
             TimerStatus::Idle => rgb(0xd6e0d6),
@@ -226,11 +1006,16 @@ impl Render for TimerView {
             .size_full()
             .bg(background_color)
             .p_5()
-            .on_key_down(Self::key_handler(timer_ticket.clone()))
+            .on_key_down(Self::key_handler(
+                view_ticket.clone(),
+                timer_ticket.clone(),
+                preset_ticket.clone(),
+            ))
+            .child(mode_tabs)
             .child(
                 div()
                     .relative()
-                    .size(px(200.))
+                    .size(diameter)
                     .child(Self::progress_circle_element(
                         timer_model.gen_progress_ratio(),
                     ))
@@ -241,9 +1026,24 @@ impl Render for TimerView {
                             .flex()
                             .justify_center()
                             .items_center()
-                            .child(Self::time_display_element(timer_model)),
-                    ),
+                            .child(Self::time_display_element(timer_model, diameter * 0.2)),
+                    )
+                    .on_mouse_down(MouseButton::Left, {
+                        let timer_ticket = timer_ticket.clone();
+                        move |event, window, app_cx| {
+                            // ダブルクリックで小窓に切り替える
+                            if event.click_count >= 2 {
+                                MiniTimerView::open(timer_ticket.clone(), window, app_cx);
+                            }
+                        }
+                    }),
             )
+            .child(Self::presets_bar_element(
+                presets,
+                view_ticket.clone(),
+                preset_ticket.clone(),
+                timer_ticket.clone(),
+            ))
             .child(
                 div()
                     .flex()
@@ -260,4 +1060,143 @@ impl Render for TimerView {
                     ),
             )
     }
+
+    fn metronome_render(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let metronome_ticket = self.metronome_ticket.clone();
+        let mode_tabs = Self::mode_tabs_element(self.mode, cx.entity());
+        let metronome_model = self.metronome_ticket.read(cx);
+
+        // 拍の表示はオーディオスレッドの状態を毎フレーム読み直す
+        if metronome_model.status == MetronomeStatus::Playing {
+            cx.on_next_frame(window, move |_model, _window, cx| {
+                cx.notify();
+            });
+        }
+        div()
+            .flex()
+            .track_focus(&self.focus_handle)
+            .flex_col()
+            .gap_3()
+            .justify_center()
+            .items_center()
+            .size_full()
+            .bg(rgb(0xd6e0d6))
+            .p_5()
+            .on_key_down(Self::metronome_key_handler(
+                cx.entity(),
+                metronome_ticket.clone(),
+            ))
+            .child(mode_tabs)
+            .child(Self::bpm_display_element(metronome_model))
+            .child(Self::signature_element(
+                metronome_model,
+                metronome_ticket.clone(),
+            ))
+            .child(Self::beat_indicator_element(
+                metronome_model.signature().beats,
+                metronome_model.current_beat(),
+            ))
+            .child(Self::metronome_buttons_element(
+                metronome_model.status,
+                metronome_ticket.clone(),
+            ))
+    }
+    fn breathing_render(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let breathing_ticket = self.breathing_ticket.clone();
+        let mode_tabs = Self::mode_tabs_element(self.mode, cx.entity());
+        let breathing_model = self.breathing_ticket.read(cx);
+        // 設定の2行が出ている間はその分も空けておく
+        let mut reserved_height = PADDING_HEIGHT + TABS_HEIGHT + BUTTON_HEIGHT + ROW_GAP * 2.;
+        if breathing_model.status != BreathingStatus::Running {
+            reserved_height += ROW_GAP + CHIP_ROW_HEIGHT * 2. + CHIP_ROW_GAP;
+        }
+        let diameter = Self::ring_diameter(window, reserved_height);
+
+        if breathing_model.status == BreathingStatus::Running {
+            cx.on_next_frame(window, move |_model, _window, cx| {
+                cx.notify();
+            });
+        }
+        let label = match breathing_model.status {
+            BreathingStatus::Idle => "▶︎",
+            BreathingStatus::Running => "■",
+            BreathingStatus::Finished => "↩︎",
+        };
+        div()
+            .flex()
+            .track_focus(&self.focus_handle)
+            .flex_col()
+            .gap_3()
+            .justify_center()
+            .items_center()
+            .size_full()
+            .bg(rgb(0xd6e0d6))
+            .p_5()
+            .on_key_down(Self::breathing_key_handler(
+                cx.entity(),
+                breathing_ticket.clone(),
+            ))
+            .child(mode_tabs)
+            .child(Self::breathing_ring_element(breathing_model, diameter))
+            .when(breathing_model.status != BreathingStatus::Running, |this| {
+                this.child(Self::breathing_settings_element(
+                    breathing_model,
+                    breathing_ticket.clone(),
+                ))
+            })
+            .child(
+                div()
+                    .flex()
+                    .justify_center()
+                    .items_center()
+                    .rounded(px(20.0))
+                    .h(px(40.))
+                    .w_full()
+                    .bg(rgb(0x4a5c4a))
+                    .text_color(rgb(0xd6e0d6))
+                    .child(label)
+                    .on_mouse_down(MouseButton::Left, move |_event, _window, app_cx| {
+                        breathing_ticket
+                            .update(app_cx, |breathing_model, cx| breathing_model.toggle(cx))
+                    }),
+            )
+    }
+    fn clock_render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let clock_ticket = self.clock_ticket.clone();
+        let mode_tabs = Self::mode_tabs_element(self.mode, cx.entity());
+        let clock_model = self.clock_ticket.read(cx);
+
+        div()
+            .flex()
+            .track_focus(&self.focus_handle)
+            .flex_col()
+            .gap_3()
+            .items_center()
+            .size_full()
+            .bg(rgb(0xd6e0d6))
+            .p_5()
+            .on_key_down(Self::clock_key_handler(cx.entity(), clock_ticket.clone()))
+            .child(mode_tabs)
+            .child(Self::zone_rows_element(clock_model, clock_ticket.clone()))
+            .child(Self::clock_input_element(clock_model))
+    }
+}
+
+impl Render for TimerView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        match self.mode {
+            Mode::Timer => self.timer_render(window, cx).into_any_element(),
+            Mode::Metronome => self.metronome_render(window, cx).into_any_element(),
+            Mode::Breathing => self.breathing_render(window, cx).into_any_element(),
+            Mode::Clock => self.clock_render(window, cx).into_any_element(),
+        }
+    }
 }